@@ -0,0 +1,72 @@
+use crate::animation::PostTransformSystems;
+use crate::animation::backup::Backup;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<Squash>();
+}
+
+/// Squashes and stretches an entity's scale with a spring, without touching its saved
+/// [`Transform`], so it's safe to use on physics bodies.
+#[derive(Component, Reflect, Copy, Clone)]
+#[reflect(Component)]
+#[require(Backup<Transform>)]
+pub struct Squash {
+    /// The scale offset the spring settles towards, where zero is the original scale.
+    pub target: Vec2,
+    /// The local point that stays in place while scaling, like the bottom of a sprite.
+    pub anchor: Vec2,
+    pub stiffness: f32,
+    pub damping: f32,
+    offset: Vec2,
+    velocity: Vec2,
+}
+
+impl Configure for Squash {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(Update, update_squash.in_set(PausableSystems));
+        app.add_systems(PostUpdate, apply_squash.in_set(PostTransformSystems::Blend));
+    }
+}
+
+impl Squash {
+    pub fn new(anchor: Vec2, stiffness: f32, damping: f32) -> Self {
+        Self {
+            target: Vec2::ZERO,
+            anchor,
+            stiffness,
+            damping,
+            offset: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+        }
+    }
+
+    /// Kicks the spring, where positive x widens and positive y lengthens.
+    pub fn impulse(&mut self, velocity: Vec2) {
+        self.velocity += velocity;
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_squash(time: Res<Time>, mut squash_query: Query<&mut Squash>) {
+    let dt = time.delta_secs();
+    for mut squash in &mut squash_query {
+        let squash = &mut *squash;
+        let acceleration =
+            squash.stiffness * (squash.target - squash.offset) - squash.damping * squash.velocity;
+        squash.velocity += acceleration * dt;
+        squash.offset += squash.velocity * dt;
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_squash(mut squash_query: Query<(&Squash, &mut Transform)>) {
+    for (squash, mut transform) in &mut squash_query {
+        let scale = (Vec2::ONE + squash.offset).max(Vec2::ZERO);
+        // Keep the anchor in place by moving the centre towards it.
+        let shift = squash.anchor * transform.scale.xy() * (Vec2::ONE - scale);
+        transform.translation += (transform.rotation * shift.extend(0.0)).with_z(0.0);
+        transform.scale *= scale.extend(1.0);
+    }
+}
@@ -14,6 +14,7 @@ pub struct AudioSettings {
     pub master_volume: f32,
     pub music_volume: f32,
     pub ui_volume: f32,
+    pub sfx_volume: f32,
 }
 
 impl Default for AudioSettings {
@@ -22,6 +23,7 @@ impl Default for AudioSettings {
             master_volume: 0.5,
             music_volume: 0.5,
             ui_volume: 0.5,
+            sfx_volume: 0.5,
         }
     }
 }
@@ -30,7 +32,7 @@ impl Configure for AudioSettings {
     fn configure(app: &mut App) {
         app.register_type::<Self>();
         app.init_resource::<Self>();
-        app.configure::<(IsMusicAudio, IsUiAudio)>();
+        app.configure::<(IsMusicAudio, IsUiAudio, IsSfxAudio)>();
         app.add_systems(
             Update,
             apply_audio_settings
@@ -48,6 +50,10 @@ impl AudioSettings {
     pub fn ui_volume(&self) -> Volume {
         Volume::Linear(self.master_volume * self.ui_volume)
     }
+
+    pub fn sfx_volume(&self) -> Volume {
+        Volume::Linear(self.master_volume * self.sfx_volume)
+    }
 }
 
 #[cfg_attr(feature = "native_dev", hot)]
@@ -55,6 +61,7 @@ fn apply_audio_settings(
     audio_settings: Res<AudioSettings>,
     music_audio_query: Query<Entity, With<IsMusicAudio>>,
     ui_audio_query: Query<Entity, With<IsUiAudio>>,
+    sfx_audio_query: Query<Entity, With<IsSfxAudio>>,
     mut volume_query: Query<(Option<&mut PlaybackSettings>, Option<&mut AudioSink>)>,
 ) {
     // Apply music volume.
@@ -80,6 +87,18 @@ fn apply_audio_settings(
             playback.volume = volume;
         }
     }
+
+    // Apply sound effect volume.
+    let volume = audio_settings.sfx_volume();
+    for entity in &sfx_audio_query {
+        let (playback, sink) = c!(volume_query.get_mut(entity));
+
+        if let Some(mut sink) = sink {
+            sink.set_volume(volume);
+        } else if let Some(mut playback) = playback {
+            playback.volume = volume;
+        }
+    }
 }
 
 #[derive(Component, Reflect, Debug)]
@@ -102,6 +121,16 @@ impl Configure for IsUiAudio {
     }
 }
 
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsSfxAudio;
+
+impl Configure for IsSfxAudio {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
 pub fn ui_audio(audio_settings: &AudioSettings, handle: Handle<AudioSource>) -> impl Bundle {
     (
         Name::new("UiSample"),
@@ -112,3 +141,12 @@ pub fn ui_audio(audio_settings: &AudioSettings, handle: Handle<AudioSource>) ->
         IsUiAudio,
     )
 }
+
+pub fn sfx_audio(audio_settings: &AudioSettings, handle: Handle<AudioSource>) -> impl Bundle {
+    (
+        Name::new("SfxSample"),
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN.with_volume(audio_settings.sfx_volume()),
+        IsSfxAudio,
+    )
+}
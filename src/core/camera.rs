@@ -1,5 +1,8 @@
 use crate::prelude::*;
 
+/// The primary camera's orthographic scale, outside of cutscenes.
+pub const CAMERA_SCALE: f32 = 0.3;
+
 pub(super) fn plugin(app: &mut App) {
     app.configure::<(CameraRoot, SmoothFollow, AbsoluteScale)>();
 }
@@ -26,7 +29,7 @@ impl FromWorld for CameraRoot {
                     Camera2d,
                     Projection::Orthographic(OrthographicProjection {
                         near: -1000.0,
-                        scale: 0.3,
+                        scale: CAMERA_SCALE,
                         ..OrthographicProjection::default_2d()
                     }),
                     Msaa::Off,
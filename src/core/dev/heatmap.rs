@@ -0,0 +1,95 @@
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::telemetry::DeathLog;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    // Set up the death heatmap.
+    app.insert_gizmo_config(
+        DeathHeatmapGizmos,
+        GizmoConfig {
+            enabled: false,
+            ..default()
+        },
+    );
+    app.add_systems(
+        Update,
+        toggle_death_heatmap.run_if(input_just_pressed(TOGGLE_KEY)),
+    );
+    app.add_systems(Update, draw_death_heatmap.run_if(death_heatmap_enabled));
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F5;
+
+/// Deaths closer together than this count towards each other's heat.
+const HEAT_RADIUS: f32 = 40.0;
+const MARKER_RADIUS: f32 = 6.0;
+const COLD_COLOR: Color = Color::srgba(1.0, 0.9, 0.2, 0.6);
+const HOT_COLOR: Color = Color::srgba(1.0, 0.1, 0.1, 0.9);
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct DeathHeatmapGizmos;
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn toggle_death_heatmap(mut gizmos: ResMut<GizmoConfigStore>) {
+    gizmos.config_mut::<DeathHeatmapGizmos>().0.enabled ^= true;
+}
+
+fn death_heatmap_enabled(gizmos: Res<GizmoConfigStore>) -> bool {
+    gizmos.config::<DeathHeatmapGizmos>().0.enabled
+}
+
+/// Heats computed for the current level, kept until the level or the death log changes.
+#[derive(Default)]
+struct HeatmapCache {
+    level_iid: String,
+    /// Each death's position and heat, from 0 to 1.
+    markers: Vec<(Vec2, f32)>,
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn draw_death_heatmap(
+    mut gizmos: Gizmos<DeathHeatmapGizmos>,
+    mut cache: Local<HeatmapCache>,
+    death_log: Res<DeathLog>,
+    level_iid: Single<&LevelIid>,
+) {
+    if death_log.is_changed() || cache.level_iid != level_iid.as_str() {
+        *cache = HeatmapCache {
+            level_iid: level_iid.as_str().to_string(),
+            markers: heatmap_markers(&death_log, level_iid.as_str()),
+        };
+    }
+
+    for &(position, t) in &cache.markers {
+        let color = COLD_COLOR.mix(&HOT_COLOR, t);
+        gizmos.circle_2d(position, MARKER_RADIUS + HEAT_RADIUS * 0.5 * t, color);
+        gizmos.cross_2d(position, MARKER_RADIUS, color);
+    }
+}
+
+fn heatmap_markers(death_log: &DeathLog, level_iid: &str) -> Vec<(Vec2, f32)> {
+    let positions = death_log
+        .iter()
+        .filter(|x| x.level_iid == level_iid)
+        .map(|x| Vec2::from_array(x.position))
+        .collect::<Vec<_>>();
+
+    // Each death's heat is the number of deaths near it, including itself.
+    let heats = positions
+        .iter()
+        .map(|a| {
+            positions
+                .iter()
+                .filter(|b| a.distance_squared(**b) <= HEAT_RADIUS * HEAT_RADIUS)
+                .count()
+        })
+        .collect::<Vec<_>>();
+    let max_heat = heats.iter().copied().max().unwrap_or(1);
+
+    positions
+        .into_iter()
+        .zip(heats)
+        .map(|(position, heat)| (position, heat as f32 / max_heat as f32))
+        .collect()
+}
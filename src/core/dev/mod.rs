@@ -3,11 +3,13 @@
 mod diagnostics;
 #[cfg(feature = "native_dev")]
 mod editor;
+mod heatmap;
 #[cfg(feature = "native_dev")]
 mod hot_patch;
 mod physics;
 mod picking;
 mod state;
+mod tuning;
 mod ui;
 
 use crate::prelude::*;
@@ -20,11 +22,13 @@ pub(super) fn plugin(app: &mut App) {
         diagnostics::plugin,
         #[cfg(feature = "native_dev")]
         editor::plugin,
+        heatmap::plugin,
         #[cfg(feature = "native_dev")]
         hot_patch::plugin,
         physics::plugin,
         picking::plugin,
         state::plugin,
+        tuning::plugin,
         ui::plugin,
     ));
 
@@ -0,0 +1,168 @@
+use bevy::reflect::ReflectRef;
+
+use crate::game::tuning::GameplayTuning;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(IsTuningOverlay, TuningValueLabel)>();
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F4;
+
+/// How much a single click nudges a value by, relative to its current size.
+const NUDGE_FACTOR: f32 = 0.05;
+/// The smallest amount a single click nudges a value by.
+const MIN_NUDGE: f32 = 0.01;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct IsTuningOverlay;
+
+impl Configure for IsTuningOverlay {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            toggle_tuning_overlay.run_if(input_just_pressed(TOGGLE_KEY)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn toggle_tuning_overlay(
+    mut commands: Commands,
+    overlay_query: Query<Entity, With<IsTuningOverlay>>,
+    tuning: ConfigRef<GameplayTuning>,
+) {
+    if !overlay_query.is_empty() {
+        for entity in &overlay_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let tuning = r!(tuning.get());
+    let mut paths = vec![];
+    collect_value_paths(tuning.as_partial_reflect(), "", &mut paths);
+
+    commands.spawn((
+        widget::overlay(500),
+        IsTuningOverlay,
+        children![(
+            Name::new("TuningPanel"),
+            Node {
+                padding: UiRect::all(Vw(1.0)),
+                row_gap: Vw(0.5),
+                ..Node::COLUMN_LEFT
+            },
+            ThemeColor::Popup.set::<BackgroundColor>(),
+            Children::spawn((
+                SpawnIter(paths.into_iter().map(tuning_row)),
+                Spawn(widget::button("Save", save_tuning)),
+            )),
+        )],
+    ));
+}
+
+/// Collects the reflect paths of every `f32` field in a tuning value.
+fn collect_value_paths(value: &dyn PartialReflect, prefix: &str, paths: &mut Vec<String>) {
+    if value.try_downcast_ref::<f32>().is_some() {
+        paths.push(prefix.to_string());
+        return;
+    }
+
+    let ReflectRef::Struct(value) = value.reflect_ref() else {
+        return;
+    };
+    for i in 0..value.field_len() {
+        let name = c!(value.name_at(i));
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        collect_value_paths(c!(value.field_at(i)), &path, paths);
+    }
+}
+
+fn tuning_row(path: String) -> impl Bundle {
+    let decrease_path = path.clone();
+    let increase_path = path.clone();
+
+    (
+        Name::new(format!("TuningRow({path})")),
+        Node {
+            column_gap: Vw(1.0),
+            ..Node::ROW_MID
+        },
+        children![
+            widget::small_button(
+                "-",
+                move |_: Trigger<Pointer<Click>>, mut tuning: ConfigMut<GameplayTuning>| {
+                    nudge_tuning(&mut tuning, &decrease_path, -1.0);
+                },
+            ),
+            widget::small_button(
+                "+",
+                move |_: Trigger<Pointer<Click>>, mut tuning: ConfigMut<GameplayTuning>| {
+                    nudge_tuning(&mut tuning, &increase_path, 1.0);
+                },
+            ),
+            (widget::label(&path), TuningValueLabel(path)),
+        ],
+    )
+}
+
+/// Nudges a value, which marks the config as modified so it gets re-applied.
+fn nudge_tuning(tuning: &mut ConfigMut<GameplayTuning>, path: &str, sign: f32) {
+    let tuning = r!(tuning.get_mut());
+    let value = r!(tuning.path_mut::<f32>(path));
+    *value += sign * (value.abs() * NUDGE_FACTOR).max(MIN_NUDGE);
+}
+
+fn save_tuning(_: Trigger<Pointer<Click>>, tuning: ConfigRef<GameplayTuning>) {
+    let tuning = r!(tuning.get());
+    let serialized = r!(ron::ser::to_string_pretty(tuning, default()));
+
+    #[cfg(feature = "native")]
+    {
+        let path = bevy::asset::io::file::FileAssetReader::get_base_path()
+            .join("assets")
+            .join(GameplayTuning::FOLDER)
+            .join(GameplayTuning::FILE);
+        r!(std::fs::write(&path, serialized + "\n"));
+        info!("Saved gameplay tuning to {}", path.display());
+    }
+
+    // There's no file system to write to, so log it for copying instead.
+    #[cfg(not(feature = "native"))]
+    info!("Gameplay tuning:\n{serialized}");
+}
+
+/// A label that displays the current value of a tuning field.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct TuningValueLabel(String);
+
+impl Configure for TuningValueLabel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(Update, update_tuning_value_labels);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_tuning_value_labels(
+    tuning: ConfigRef<GameplayTuning>,
+    mut label_query: Query<(&TuningValueLabel, &mut RichText)>,
+) {
+    let tuning = rq!(tuning.get());
+    for (label, mut text) in &mut label_query {
+        let value = c!(tuning.path::<f32>(label.0.as_str()));
+        let display = format!("{}: {value:.3}", label.0);
+        if text.sections.first().is_some_and(|x| x.value == display) {
+            continue;
+        }
+        c!(text.sections.first_mut()).value = display;
+    }
+}
@@ -22,5 +22,6 @@ pub struct PausableSystems;
 impl Configure for PausableSystems {
     fn configure(app: &mut App) {
         app.configure_sets(Update, PausableSystems.run_if(Pause::is_disabled));
+        app.configure_sets(FixedUpdate, PausableSystems.run_if(Pause::is_disabled));
     }
 }
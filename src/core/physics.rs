@@ -1,16 +1,15 @@
-use avian2d::math::Vector;
-
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(PhysicsPlugins::default().with_length_unit(PIXELS_PER_METER));
-    app.insert_resource(Gravity(Vector::NEG_Y * 500.0));
+    // The length unit and gravity are set by `GameplayTuning` when it loads.
+    app.add_plugins(
+        // Gameplay runs in `FixedUpdate`, so smooth out rendering between fixed steps.
+        PhysicsPlugins::default().set(PhysicsInterpolationPlugin::interpolate_all()),
+    );
 
     app.add_systems(StateFlush, Pause.on_edge(unpause_physics, pause_physics));
 }
 
-const PIXELS_PER_METER: f32 = 16.0;
-
 #[cfg_attr(feature = "native_dev", hot)]
 fn unpause_physics(mut physics_time: ResMut<Time<Physics>>) {
     physics_time.unpause();
@@ -1,7 +1,19 @@
+use std::collections::BTreeMap;
+
+use bevy_common_assets::ron::RonAssetPlugin;
+
 use crate::prelude::*;
 use crate::screen::Screen;
 
+/// Frames shorter than this are stretched, so a zero duration can't stall the animation.
+const MIN_FRAME_DURATION: f32 = 0.001;
+
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SpriteAnimation>();
+    app.add_plugins(RonAssetPlugin::<AnimationClips>::new(&["clips.ron"]));
+
+    app.add_event::<AnimationFrameEvent>();
+
     app.add_systems(
         Update,
         Screen::Gameplay
@@ -10,45 +22,202 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Reflect, Deref, DerefMut)]
-#[reflect(Component)]
-pub struct AnimationTimer(pub Timer);
+/// Named animation clips for a spritesheet, loaded from a `.clips.ron` file next to it
+/// or built from the tags in its Aseprite export.
+#[derive(Asset, Reflect, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct AnimationClips(pub BTreeMap<String, AnimationClip>);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
-#[reflect(Component)]
-pub struct AnimationIndices {
-    first: usize,
-    last: usize,
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationClip {
+    pub frames: Vec<AnimationFrame>,
+    #[serde(default)]
+    pub mode: AnimationMode,
 }
 
-impl AnimationIndices {
-    pub fn new(first: usize, last: usize) -> Self {
-        Self { first, last }
+impl AnimationClip {
+    /// Returns the frame after `frame` and whether it's played in reverse,
+    /// or `None` if the clip has finished.
+    fn next_frame(&self, frame: usize, reversed: bool) -> Option<(usize, bool)> {
+        let last = self.frames.len().saturating_sub(1);
+        match self.mode {
+            AnimationMode::Loop => Some((if frame >= last { 0 } else { frame + 1 }, false)),
+            AnimationMode::Once => (frame < last).then_some((frame + 1, false)),
+            AnimationMode::PingPong if last == 0 => Some((0, false)),
+            AnimationMode::PingPong if reversed && frame == 0 => Some((1, false)),
+            AnimationMode::PingPong if reversed => Some((frame - 1, true)),
+            AnimationMode::PingPong if frame >= last => Some((last - 1, true)),
+            AnimationMode::PingPong => Some((frame + 1, false)),
+        }
     }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationFrame {
+    /// The index into the sprite's texture atlas.
+    pub index: usize,
+    /// How long the frame is shown for, in seconds.
+    pub duration: f32,
+    /// Sent as an [`AnimationFrameEvent`] when the frame is shown.
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationMode {
+    #[default]
+    Loop,
+    /// Stops on the last frame.
+    Once,
+    /// Plays forwards then backwards, forever.
+    PingPong,
+}
+
+/// Plays a clip from [`AnimationClips`] on the sprite's texture atlas.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct SpriteAnimation {
+    clips: Handle<AnimationClips>,
+    clip: String,
+    frame: usize,
+    elapsed: f32,
+    reversed: bool,
+    finished: bool,
+    /// Whether the current frame has been applied to the sprite yet.
+    shown: bool,
+}
 
-    pub fn single(index: usize) -> Self {
+impl SpriteAnimation {
+    pub fn new(clips: Handle<AnimationClips>, clip: impl Into<String>) -> Self {
         Self {
-            first: index,
-            last: index,
+            clips,
+            clip: clip.into(),
+            frame: 0,
+            elapsed: 0.0,
+            reversed: false,
+            finished: false,
+            shown: false,
         }
     }
+
+    /// Switches to a clip, restarting it unless it's already playing.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            *self = Self::new(self.clips.clone(), clip);
+        }
+    }
+}
+
+/// Sent when a frame with an event is shown.
+#[derive(Event, Reflect, Debug, Clone, PartialEq, Eq)]
+pub struct AnimationFrameEvent {
+    pub entity: Entity,
+    pub clip: String,
+    pub event: String,
 }
 
 fn animate_sprite(
     time: Res<Time>,
-    mut query: Query<(&mut Sprite, &mut AnimationTimer, &AnimationIndices)>,
+    clips_assets: Res<Assets<AnimationClips>>,
+    mut query: Query<(Entity, &mut Sprite, &mut SpriteAnimation)>,
+    mut frame_event_writer: EventWriter<AnimationFrameEvent>,
 ) {
-    for (mut sprite, mut timer, indices) in &mut query {
-        timer.tick(time.delta());
-
-        if timer.just_finished()
-            && let Some(atlas) = &mut sprite.texture_atlas
-        {
-            atlas.index = if atlas.index >= indices.last {
-                indices.first
-            } else {
-                atlas.index + 1
+    for (entity, mut sprite, mut animation) in &mut query {
+        let clips = cq!(clips_assets.get(&animation.clips));
+        let clip = c!(clips.0.get(&animation.clip));
+        cq!(!clip.frames.is_empty());
+        let animation = &mut *animation;
+
+        // The clip may have been hot-reloaded with fewer frames.
+        if animation.frame >= clip.frames.len() {
+            *animation = SpriteAnimation::new(animation.clips.clone(), animation.clip.clone());
+        }
+
+        if animation.shown {
+            if animation.finished {
+                continue;
+            }
+            animation.elapsed += time.delta_secs();
+        }
+
+        // Catch up on every frame that should have been shown by now.
+        loop {
+            if !animation.shown {
+                let frame = &clip.frames[animation.frame];
+                if let Some(atlas) = &mut sprite.texture_atlas {
+                    atlas.index = frame.index;
+                }
+                if let Some(event) = &frame.event {
+                    frame_event_writer.write(AnimationFrameEvent {
+                        entity,
+                        clip: animation.clip.clone(),
+                        event: event.clone(),
+                    });
+                }
+                animation.shown = true;
+            }
+
+            let duration = clip.frames[animation.frame]
+                .duration
+                .max(MIN_FRAME_DURATION);
+            if animation.elapsed < duration {
+                break;
+            }
+
+            let Some((frame, reversed)) = clip.next_frame(animation.frame, animation.reversed)
+            else {
+                animation.finished = true;
+                break;
+            };
+            animation.elapsed -= duration;
+            animation.frame = frame;
+            animation.reversed = reversed;
+            animation.shown = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(frame_count: usize, mode: AnimationMode) -> AnimationClip {
+        AnimationClip {
+            frames: (0..frame_count)
+                .map(|index| AnimationFrame {
+                    index,
+                    duration: 0.1,
+                    event: None,
+                })
+                .collect(),
+            mode,
+        }
+    }
+
+    fn play(clip: &AnimationClip, steps: usize) -> Vec<usize> {
+        let mut frames = vec![0];
+        let (mut frame, mut reversed) = (0, false);
+        for _ in 0..steps {
+            let Some(next) = clip.next_frame(frame, reversed) else {
+                break;
             };
+            (frame, reversed) = next;
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn test_next_frame() {
+        for (clip, want) in [
+            (clip(3, AnimationMode::Loop), vec![0, 1, 2, 0, 1, 2, 0]),
+            (clip(3, AnimationMode::Once), vec![0, 1, 2]),
+            (clip(3, AnimationMode::PingPong), vec![0, 1, 2, 1, 0, 1, 2]),
+            (clip(1, AnimationMode::PingPong), vec![0, 0, 0, 0, 0, 0, 0]),
+        ] {
+            assert_eq!(play(&clip, 6), want, "{:?}", clip.mode);
         }
     }
 }
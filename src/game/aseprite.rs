@@ -0,0 +1,227 @@
+//! Loads spritesheets exported from Aseprite with `--format json-array --list-tags`.
+//!
+//! A `.clips.ron` file next to the export, like `player_spritesheet.clips.ron` for
+//! `player_spritesheet.aseprite.json`, replaces the tags' clips with the same names.
+
+use std::path::Path;
+
+use bevy::asset::AssetLoader;
+use bevy::asset::LoadContext;
+use bevy::asset::ReadAssetBytesError;
+use bevy::asset::io::AssetReaderError;
+use bevy::asset::io::Reader;
+
+use super::animated_sprite::AnimationClip;
+use super::animated_sprite::AnimationClips;
+use super::animated_sprite::AnimationFrame;
+use super::animated_sprite::AnimationMode;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AsepriteSheet>();
+    app.init_asset::<AsepriteSheet>();
+    app.register_asset_loader(AsepriteLoader);
+}
+
+/// A spritesheet along with its atlas layout and an animation clip for each tag.
+#[derive(Asset, Reflect, Debug, Clone)]
+pub struct AsepriteSheet {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: Handle<AnimationClips>,
+}
+
+impl AsepriteSheet {
+    pub fn sprite(&self) -> Sprite {
+        Sprite::from_atlas_image(
+            self.image.clone(),
+            TextureAtlas {
+                layout: self.layout.clone(),
+                index: 0,
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = AsepriteSheet;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes).await?;
+        let json = serde_json::from_slice::<AsepriteJson>(&bytes)?;
+
+        // The image path is relative to the JSON file.
+        let image_path = load_context
+            .path()
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(&json.meta.image);
+        let image = load_context.load(image_path);
+
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(json.meta.size.w, json.meta.size.h));
+        for frame in &json.frames {
+            let rect = &frame.frame;
+            layout.add_texture(URect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h));
+        }
+
+        let mut clips = AnimationClips::default();
+        for tag in &json.meta.frame_tags {
+            clips.0.insert(tag.name.clone(), tag.to_clip(&json.frames)?);
+        }
+
+        let clips_path = load_context
+            .path()
+            .with_file_name(format!("{}.clips.ron", file_stem(load_context.path())));
+        match load_context.read_asset_bytes(clips_path).await {
+            Ok(bytes) => clips
+                .0
+                .extend(ron::de::from_bytes::<AnimationClips>(&bytes)?.0),
+            Err(ReadAssetBytesError::AssetReaderError(AssetReaderError::NotFound(_))) => {},
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(AsepriteSheet {
+            image,
+            layout: load_context.add_labeled_asset("layout".to_string(), layout),
+            clips: load_context.add_labeled_asset("clips".to_string(), clips),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+/// The file name without its `.aseprite.json` extension.
+fn file_stem(path: &Path) -> &str {
+    let name = path
+        .file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    name.strip_suffix(".aseprite.json").unwrap_or(name)
+}
+
+#[derive(Deserialize)]
+struct AsepriteJson {
+    frames: Vec<AsepriteFrame>,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    /// In milliseconds.
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    image: String,
+    size: AsepriteSize,
+    #[serde(default)]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: AsepriteDirection,
+    /// How many times to play the tag, where missing means forever.
+    #[serde(default)]
+    repeat: Option<String>,
+    /// The tag's user data, which lists frame events as `frame:event` pairs separated by
+    /// commas, with frames counted from the start of the tag. For example `1:footstep`.
+    #[serde(default)]
+    data: Option<String>,
+}
+
+impl AsepriteTag {
+    fn to_clip(&self, frames: &[AsepriteFrame]) -> Result<AnimationClip, String> {
+        if self.from > self.to || self.to >= frames.len() {
+            return Err(format!("tag '{}' is out of range", self.name));
+        }
+
+        let mut events = HashMap::<usize, String>::default();
+        for entry in self.data.iter().flat_map(|x| x.split(',')) {
+            let (frame, event) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("tag '{}' has invalid event '{entry}'", self.name))?;
+            let frame = frame
+                .trim()
+                .parse::<usize>()
+                .map_err(|e| format!("tag '{}' has invalid event '{entry}': {e}", self.name))?;
+            events.insert(frame, event.trim().to_string());
+        }
+
+        let mut clip_frames = (self.from..=self.to)
+            .enumerate()
+            .map(|(i, index)| AnimationFrame {
+                index,
+                duration: frames[index].duration as f32 / 1000.0,
+                event: events.remove(&i),
+            })
+            .collect::<Vec<_>>();
+        if matches!(
+            self.direction,
+            AsepriteDirection::Reverse | AsepriteDirection::PingpongReverse,
+        ) {
+            clip_frames.reverse();
+        }
+
+        let mode = if matches!(
+            self.direction,
+            AsepriteDirection::Pingpong | AsepriteDirection::PingpongReverse,
+        ) {
+            AnimationMode::PingPong
+        } else if self.repeat.as_deref() == Some("1") {
+            AnimationMode::Once
+        } else {
+            AnimationMode::Loop
+        };
+
+        Ok(AnimationClip {
+            frames: clip_frames,
+            mode,
+        })
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum AsepriteDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
@@ -0,0 +1,355 @@
+//! The boss fought inside an arena on the final level, which ends the run when defeated.
+
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::chain::ChainPart;
+use crate::game::chain::ChainWeight;
+use crate::game::chain::ConnectedChain;
+use crate::game::chain_movement::GameLayer;
+use crate::game::end_sequence::StartEndSequenceEvent;
+use crate::game::enemy::Aim;
+use crate::game::enemy::AttackCooldown;
+use crate::game::enemy::EnemyAttack;
+use crate::game::enemy::EnemyBundle;
+use crate::game::enemy::EnemySystems;
+use crate::game::enemy::Patrol;
+use crate::game::fire::IgniteFire;
+use crate::game::health::Health;
+use crate::game::health::Invulnerable;
+use crate::game::health::PlayerDamage;
+use crate::game::player::Player;
+use crate::game::player_chain::DespawnTimer;
+use crate::game::projectile::ShootProjectile;
+use crate::game::tree::TreeAssets;
+use crate::game::tree::apple;
+use crate::game::vines::KillsPlayer;
+use crate::game::vines::on_collision_damages_player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+const BOSS_SIZE: f32 = 96.0;
+const BOSS_COLOR: Color = Color::srgb(0.56, 0.24, 0.59);
+const DEFAULT_BOSS_HEALTH: u32 = 9;
+/// How fast the player has to be swinging to hurt the boss instead of getting hurt.
+const MIN_SWING_SPEED: f32 = 250.0;
+/// How fast a released chain weight has to be moving to hurt the boss.
+const MIN_WEIGHT_SPEED: f32 = 150.0;
+const HIT_INVULNERABILITY_DURATION: f32 = 1.0;
+const DEFEATED_ALPHA: f32 = 0.4;
+/// How far inside the arena the player has to be before it locks.
+const ARENA_LOCK_MARGIN: f32 = 48.0;
+const ARENA_WALL_THICKNESS: f32 = 16.0;
+const ARENA_WALL_COLOR: Color = Color::srgb(0.76, 0.23, 0.13);
+const APPLE_SPREAD: f32 = 80.0;
+const APPLE_SPEED: f32 = 450.0;
+const VINE_SIZE: Vec2 = Vec2::new(48.0, 40.0);
+const VINE_COLOR: Color = Color::srgb(0.2, 0.55, 0.25);
+const VINE_DURATION: f32 = 2.0;
+/// The spacing between fires, which matches the fire grid.
+const FIRE_SPACING: f32 = 16.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(Boss, BossDefeated, Arena, ArenaLocked, ArenaWall)>();
+
+    app.register_ldtk_entity::<BossBundle>("boss");
+    app.register_ldtk_entity::<ArenaBundle>("arena");
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(
+                (process_bosses, lock_arenas, update_boss_phase)
+                    .chain()
+                    .before(EnemySystems),
+            )
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Component, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct Boss {
+    pub phase: BossPhase,
+}
+
+/// Each phase has its own attack, and the boss moves on to the next after every third of its health.
+#[derive(Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub enum BossPhase {
+    /// Throws a spread of apples.
+    #[default]
+    Apples,
+    /// Grows vines out of the arena floor under the player.
+    Vines,
+    /// Sets the ground around the player alight.
+    Fire,
+}
+
+impl BossPhase {
+    fn from_health(health: &Health) -> Self {
+        let lost = health.max - health.current;
+        match 3 * lost / health.max.max(1) {
+            0 => Self::Apples,
+            1 => Self::Vines,
+            _ => Self::Fire,
+        }
+    }
+}
+
+/// Stops the boss fighting, but keeps it around for the end sequence.
+#[derive(Component, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct BossDefeated;
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct BossBundle {
+    boss: Boss,
+    #[with(boss_health)]
+    health: Health,
+    #[ldtk_entity]
+    enemy: EnemyBundle,
+
+    collision_events_enabled: CollisionEventsEnabled,
+}
+
+fn boss_health(entity_instance: &EntityInstance) -> Health {
+    Health::new(
+        entity_instance
+            .get_int_field("health")
+            .map_or(DEFAULT_BOSS_HEALTH, |&x| x.max(1) as u32),
+    )
+}
+
+/// Walls the player in with the boss once they're inside.
+#[derive(Component, Reflect, Copy, Clone, Default, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct Arena {
+    pub size: Vec2,
+}
+
+impl Arena {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        Self {
+            size: Vec2::new(entity_instance.width as f32, entity_instance.height as f32),
+        }
+    }
+}
+
+/// The fight is on, until the boss is defeated.
+#[derive(Component, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct ArenaLocked;
+
+#[derive(Component, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+struct ArenaWall;
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct ArenaBundle {
+    #[with(Arena::from_entity_instance)]
+    arena: Arena,
+}
+
+fn process_bosses(mut commands: Commands, boss_query: Query<Entity, Added<Boss>>) {
+    for entity in &boss_query {
+        commands
+            .entity(entity)
+            .insert((
+                Name::new("Boss"),
+                Sprite::from_color(BOSS_COLOR, Vec2::splat(BOSS_SIZE)),
+                RigidBody::Kinematic,
+                Collider::rectangle(BOSS_SIZE, BOSS_SIZE),
+                // Apples pass through it, but the player and chains don't.
+                CollisionLayers::new(
+                    GameLayer::TreeLayer,
+                    [GameLayer::Default, GameLayer::ChainLayer],
+                ),
+            ))
+            .observe(on_boss_hit)
+            .observe(on_boss_attack);
+    }
+}
+
+fn lock_arenas(
+    mut commands: Commands,
+    player: Single<&GlobalTransform, With<Player>>,
+    arena_query: Query<
+        (Entity, &Arena, &Transform, &GlobalTransform, &ChildOf),
+        Without<ArenaLocked>,
+    >,
+) {
+    let player_pos = player.translation().xy();
+    for (entity, arena, transform, gt, child_of) in &arena_query {
+        let inner =
+            Rect::from_center_size(gt.translation().xy(), arena.size).inflate(-ARENA_LOCK_MARGIN);
+        if !inner.contains(player_pos) {
+            continue;
+        }
+
+        commands.entity(entity).insert(ArenaLocked);
+
+        // Walls go just outside the edges, so they can't trap the player inside them.
+        let center = transform.translation.xy();
+        let half_size = arena.size / 2.0;
+        let offset = half_size + ARENA_WALL_THICKNESS / 2.0;
+        let walls = [
+            (Vec2::new(0.0, offset.y), Vec2::new(arena.size.x, 0.0)),
+            (Vec2::new(0.0, -offset.y), Vec2::new(arena.size.x, 0.0)),
+            (Vec2::new(-offset.x, 0.0), Vec2::new(0.0, arena.size.y)),
+            (Vec2::new(offset.x, 0.0), Vec2::new(0.0, arena.size.y)),
+        ];
+        commands.entity(child_of.parent()).with_children(|parent| {
+            for (wall_offset, length) in walls {
+                let size = length + Vec2::splat(ARENA_WALL_THICKNESS);
+                parent.spawn((
+                    Name::new("ArenaWall"),
+                    ArenaWall,
+                    Sprite::from_color(ARENA_WALL_COLOR, size),
+                    Transform::from_translation((center + wall_offset).extend(1.0)),
+                    RigidBody::Static,
+                    Collider::rectangle(size.x, size.y),
+                    CollisionLayers::new(
+                        [GameLayer::Default, GameLayer::WallLayer],
+                        LayerMask::ALL,
+                    ),
+                ));
+            }
+        });
+    }
+}
+
+fn on_boss_hit(
+    trigger: Trigger<OnCollisionStart>,
+    mut boss_query: Query<
+        (&mut Health, &GlobalTransform, Has<Invulnerable>),
+        Without<BossDefeated>,
+    >,
+    player_query: Query<(&LinearVelocity, Option<&ConnectedChain>), With<Player>>,
+    weight_query: Query<(&LinearVelocity, &ChainPart), With<ChainWeight>>,
+    mut damage_event_writer: EventWriter<PlayerDamage>,
+    mut commands: Commands,
+) {
+    let boss_entity = trigger.target();
+    let other_entity = trigger.collider;
+    let (mut health, transform, is_invulnerable) = rq!(boss_query.get_mut(boss_entity));
+
+    let is_hit = if let Ok((velocity, chain)) = player_query.get(other_entity) {
+        if chain.is_none() || velocity.length() < MIN_SWING_SPEED {
+            // Walking into the boss hurts, so it has to be swung into.
+            damage_event_writer.write(PlayerDamage {
+                source: boss_entity,
+                cause: Name::new("Boss"),
+                position: transform.translation().xy(),
+            });
+            return;
+        }
+        true
+    } else if let Ok((velocity, part)) = weight_query.get(other_entity) {
+        // The chain the player is holding on to doesn't count until it's let go of.
+        let is_held = player_query
+            .iter()
+            .any(|(_, chain)| chain.is_some_and(|x| x.0 == part.0));
+        !is_held && velocity.length() >= MIN_WEIGHT_SPEED
+    } else {
+        false
+    };
+    rq!(is_hit && !is_invulnerable && health.current > 0);
+
+    health.current -= 1;
+    commands
+        .entity(boss_entity)
+        .insert(Invulnerable(Timer::from_seconds(
+            HIT_INVULNERABILITY_DURATION,
+            TimerMode::Once,
+        )));
+}
+
+fn update_boss_phase(
+    mut commands: Commands,
+    mut boss_query: Query<
+        (Entity, &mut Boss, &Health, &mut Sprite),
+        (Changed<Health>, Without<BossDefeated>),
+    >,
+    arena_query: Query<Entity, With<ArenaLocked>>,
+    wall_query: Query<Entity, With<ArenaWall>>,
+    mut end_event_writer: EventWriter<StartEndSequenceEvent>,
+) {
+    for (entity, mut boss, health, mut sprite) in &mut boss_query {
+        boss.phase = BossPhase::from_health(health);
+        if health.current > 0 {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .insert((BossDefeated, LinearVelocity::ZERO))
+            .remove::<(Collider, Invulnerable, Patrol, AttackCooldown)>();
+        sprite.color.set_alpha(DEFEATED_ALPHA);
+
+        for arena in &arena_query {
+            commands.entity(arena).remove::<ArenaLocked>();
+        }
+        for wall in &wall_query {
+            commands.entity(wall).despawn();
+        }
+
+        end_event_writer.write(StartEndSequenceEvent);
+    }
+}
+
+fn on_boss_attack(
+    trigger: Trigger<EnemyAttack>,
+    boss_query: Query<(&Boss, &GlobalTransform, &Aim), Without<BossDefeated>>,
+    arena: Option<Single<(&Arena, &GlobalTransform), With<ArenaLocked>>>,
+    gravity: Res<Gravity>,
+    mut commands: Commands,
+    tree_assets: Res<TreeAssets>,
+    mut shoot_event_writer: EventWriter<ShootProjectile>,
+    mut ignite_event_writer: EventWriter<IgniteFire>,
+) {
+    let boss_entity = trigger.target();
+    let (boss, transform, aim) = rq!(boss_query.get(boss_entity));
+    // Only fight once the player is locked in.
+    let (arena, arena_transform) = *rq!(arena);
+    let target = trigger.target;
+
+    match boss.phase {
+        BossPhase::Apples => {
+            let start = transform.translation().xy();
+            for offset in [-APPLE_SPREAD, 0.0, APPLE_SPREAD] {
+                let attack = EnemyAttack {
+                    target: target + Vec2::X * offset,
+                    ..*trigger.event()
+                };
+                let velocity = aim.launch_velocity(start, &attack, APPLE_SPEED, gravity.0);
+                shoot_event_writer.write(apple(&tree_assets, boss_entity, start, velocity));
+            }
+        },
+        BossPhase::Vines => {
+            let floor_y = arena_transform.translation().y - arena.size.y / 2.0;
+            for offset in [-1.0, 0.0, 1.0] {
+                let pos = Vec2::new(target.x + offset * VINE_SIZE.x, floor_y + VINE_SIZE.y / 2.0);
+                commands
+                    .spawn((
+                        Name::new("Vines"),
+                        KillsPlayer,
+                        Sprite::from_color(VINE_COLOR, VINE_SIZE),
+                        Transform::from_translation(pos.extend(1.0)),
+                        RigidBody::Static,
+                        Sensor,
+                        CollisionEventsEnabled,
+                        Collider::rectangle(VINE_SIZE.x, VINE_SIZE.y),
+                        DespawnTimer(Timer::from_seconds(VINE_DURATION, TimerMode::Once)),
+                        DespawnOnExitState::<Screen>::default(),
+                    ))
+                    .observe(on_collision_damages_player);
+            }
+        },
+        BossPhase::Fire => {
+            for offset in [-FIRE_SPACING, 0.0, FIRE_SPACING] {
+                ignite_event_writer.write(IgniteFire {
+                    position: target + Vec2::X * offset,
+                });
+            }
+        },
+    }
+}
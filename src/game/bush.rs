@@ -0,0 +1,53 @@
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::fire::FireCellBurntOut;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_default_ldtk_int_cell_for_layer::<BushBundle>("bush");
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(burn_bushes)
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Bush;
+
+#[derive(Bundle, Default, LdtkIntCell)]
+struct BushBundle {
+    bush: Bush,
+}
+
+/// Removes bushes once the fire on them has burnt out.
+fn burn_bushes(
+    mut commands: Commands,
+    mut burnt_out_event_reader: EventReader<FireCellBurntOut>,
+    bush_query: Query<(Entity, &GridCoords, &ChildOf), With<Bush>>,
+    layer_query: Query<&LayerMetadata>,
+) {
+    let burnt_out = burnt_out_event_reader
+        .read()
+        .map(|x| x.position)
+        .collect::<Vec<_>>();
+    if burnt_out.is_empty() {
+        return;
+    }
+
+    for (entity, coords, child_of) in &bush_query {
+        // Bushes sit on a coarser grid than fire, so match by position instead of coords.
+        let grid_size = c!(layer_query.get(child_of.parent())).grid_size as f32;
+        let cell = IVec2::new(coords.x, coords.y);
+        if burnt_out
+            .iter()
+            .any(|position| (position / grid_size).floor().as_ivec2() == cell)
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -2,15 +2,25 @@ use avian2d::math::Vector;
 use bevy_ecs_ldtk::prelude::*;
 
 use crate::game::chain_movement::GameLayer;
+use crate::game::chain_movement::PlayerChainEvent;
+use crate::game::fire::BurntOut;
+use crate::game::fire::Flammable;
+use crate::game::fire::OnFire;
 use crate::game::player_chain::DyingChain;
 use crate::game::player_chain::GeneratedChain;
 use crate::game::player_chain::GeneratedChainJoint;
+use crate::game::tuning::GameplayTuning;
 use crate::prelude::*;
 use crate::screen::Screen;
 
 pub const CHAIN_SIZE: f32 = 0.16;
 pub const CHAIN_IMAGE_SIZE: f32 = 100.0;
 const INTENDED_CHAIN_SIZE: f32 = 100.0;
+const CHAIN_COMPLIANCE: f32 = 0.000001;
+/// Burning chains sag and stretch before they snap.
+const BURNING_CHAIN_COMPLIANCE: f32 = 0.0005;
+/// How long a chain part burns for before it snaps.
+const CHAIN_FUEL: f32 = 1.5;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_ldtk_entity::<ChainImportBundle>("chain");
@@ -20,7 +30,13 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
         Screen::Gameplay
-            .on_update((process_chain, process_chain_immunity_timer))
+            .on_update((process_chain, weaken_burning_chains))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        FixedUpdate,
+        Screen::Gameplay
+            .on_update(process_chain_immunity_timer)
             .in_set(PausableSystems),
     );
 }
@@ -80,6 +96,11 @@ pub struct ChainPart(pub String);
 #[reflect(Component)]
 pub struct PivotChainPart;
 
+/// The heavy part at the free end of a chain
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct ChainWeight;
+
 /// Chain that's imported from the map editor
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
@@ -105,6 +126,7 @@ struct ChainBundle {
     collision_event_enabled: CollisionEventsEnabled,
     chain_part: ChainPart,
     chain_layer: CollisionLayers,
+    flammable: Flammable,
 }
 
 impl ChainBundle {
@@ -131,6 +153,10 @@ impl ChainBundle {
             collision_event_enabled: CollisionEventsEnabled,
             chain_part,
             chain_layer: CollisionLayers::new(GameLayer::ChainLayer, LayerMask::ALL),
+            flammable: Flammable {
+                fuel: CHAIN_FUEL,
+                half_size: Vec2::splat(CHAIN_SIZE * INTENDED_CHAIN_SIZE / 2.0),
+            },
         }
     }
 }
@@ -203,7 +229,9 @@ pub fn convert_chain_to_parts(
                         ChainPart(chain_id.to_string()),
                     ))
                     .insert_if(GeneratedChain, || generated_chain)
+                    .insert_if(ChainWeight, || last)
                     .observe(observe_chain_collision)
+                    .observe(snap_burnt_chain)
                     .id();
 
                 // joint between the two entities
@@ -212,7 +240,7 @@ pub fn convert_chain_to_parts(
                         RevoluteJoint::new(last_chain, next_chain)
                             .with_local_anchor_2(Vector::Y * 1.0 * CHAIN_SIZE * INTENDED_CHAIN_SIZE)
                             .with_angle_limits(-0.01, 0.01)
-                            .with_compliance(0.000001),
+                            .with_compliance(CHAIN_COMPLIANCE),
                     )
                     .insert_if(GeneratedChainJoint, || generated_chain);
 
@@ -231,6 +259,7 @@ pub fn convert_chain_to_parts(
                         .insert(PivotChainPart)
                         .insert_if(GeneratedChain, || generated_chain)
                         .observe(observe_chain_collision)
+                        .observe(snap_burnt_chain)
                         .id(),
                 );
             }
@@ -246,6 +275,7 @@ fn observe_chain_collision(
         (Entity, Option<&ChainImmunity>),
         (With<CanAttachChain>, Without<ConnectedChain>),
     >,
+    tuning: ConfigRef<GameplayTuning>,
 ) {
     let chain_entity = trigger.target();
     let other_entity = trigger.collider;
@@ -264,6 +294,8 @@ fn observe_chain_collision(
             return;
         }
 
+        let tuning = r!(tuning.get());
+
         // create filter so that we don't collide with the chain while on it
         let filters = *LayerMask::ALL & !(GameLayer::ChainLayer.to_bits());
         let ignore_chain_collision_layer = CollisionLayers::new(LayerMask::DEFAULT, filters);
@@ -271,7 +303,7 @@ fn observe_chain_collision(
         commands
             .entity(other_entity)
             .insert(ConnectedChain(chain_id.to_string()))
-            .insert(GravityScale(1.0))
+            .insert(tuning.player.gravity_scale(true))
             .insert(ignore_chain_collision_layer);
 
         commands.spawn((
@@ -295,3 +327,44 @@ fn process_chain_immunity_timer(
         }
     }
 }
+
+fn weaken_burning_chains(
+    burning_query: Query<(), (With<ChainPart>, Added<OnFire>)>,
+    mut joint_query: Query<&mut RevoluteJoint>,
+) {
+    if burning_query.is_empty() {
+        return;
+    }
+
+    for mut joint in &mut joint_query {
+        if burning_query.contains(joint.entity1) || burning_query.contains(joint.entity2) {
+            *joint = joint.clone().with_compliance(BURNING_CHAIN_COMPLIANCE);
+        }
+    }
+}
+
+/// Breaks the chain at a part that has burnt through.
+fn snap_burnt_chain(
+    trigger: Trigger<BurntOut>,
+    mut commands: Commands,
+    joint_query: Query<(Entity, &RevoluteJoint)>,
+    player_joint_query: Query<&DistanceJoint, With<ChainJoint>>,
+    mut chain_event_writer: EventWriter<PlayerChainEvent>,
+) {
+    let part_entity = trigger.target();
+    for (joint_entity, joint) in &joint_query {
+        if joint.entity1 == part_entity || joint.entity2 == part_entity {
+            commands.entity(joint_entity).despawn();
+        }
+    }
+
+    // Drop the player if they're holding on to the part that burnt away.
+    if player_joint_query
+        .iter()
+        .any(|x| x.entity1 == part_entity || x.entity2 == part_entity)
+    {
+        chain_event_writer.write(PlayerChainEvent::LeaveChain);
+    }
+
+    commands.entity(part_entity).despawn();
+}
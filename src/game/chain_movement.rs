@@ -2,6 +2,9 @@ use crate::game::chain::ChainImmunity;
 use crate::game::chain::ChainJoint;
 use crate::game::chain::ConnectedChain;
 use crate::game::player::Player;
+use crate::game::player::PlayerAction;
+use crate::game::timeline::PlayerInputLocked;
+use crate::game::tuning::GameplayTuning;
 use crate::prelude::*;
 use crate::screen::Screen;
 
@@ -11,7 +14,10 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
         Screen::Gameplay
-            .on_update((handle_keyboard_input, handle_player_chain_event))
+            .on_update((
+                handle_input.run_if(PlayerInputLocked::is_disabled),
+                handle_player_chain_event,
+            ))
             .in_set(PausableSystems),
     );
 }
@@ -22,6 +28,7 @@ pub enum GameLayer {
     Default, // Layer 0 - the default layer that objects are assigned to
     ChainLayer, // for chains
     TreeLayer,  // for trees
+    WallLayer,  // for level walls, used for wall detection
 }
 
 #[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,18 +36,15 @@ pub enum PlayerChainEvent {
     LeaveChain,
 }
 
-fn handle_keyboard_input(
+fn handle_input(
     mut player_chain_event: EventWriter<PlayerChainEvent>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState<PlayerAction>>,
     connected_chain: Single<Has<ConnectedChain>, With<Player>>,
 ) {
     if !*connected_chain {
         return;
     }
-    let leave_chain =
-        keyboard_input.any_just_pressed([KeyCode::KeyW, KeyCode::ArrowUp, KeyCode::Space]);
-
-    if leave_chain {
+    if action_state.just_pressed(&PlayerAction::Jump) {
         player_chain_event.write(PlayerChainEvent::LeaveChain);
     }
 }
@@ -49,11 +53,13 @@ fn handle_player_chain_event(
     mut chain_event_reader: EventReader<PlayerChainEvent>,
     mut commands: Commands,
     mut player_query: Single<
-        (Entity, &mut Transform, &mut LinearVelocity, &ConnectedChain),
+        (Entity, &mut Rotation, &mut LinearVelocity, &ConnectedChain),
         With<Player>,
     >,
     joint_query: Query<(Entity, &DistanceJoint), With<ChainJoint>>,
+    tuning: ConfigRef<GameplayTuning>,
 ) {
+    let tuning = r!(tuning.get());
     for chain_event in chain_event_reader.read() {
         match chain_event {
             PlayerChainEvent::LeaveChain => {
@@ -66,10 +72,10 @@ fn handle_player_chain_event(
                         Timer::from_seconds(1.0, TimerMode::Once),
                         player_query.3.0.to_string(),
                     ))
-                    .insert(GravityScale(2.0))
+                    .insert(tuning.player.gravity_scale(false))
                     .insert(CollisionLayers::DEFAULT);
 
-                player_query.1.rotation = Quat::default();
+                *player_query.1 = Rotation::default();
 
                 for joints in joint_query {
                     let joint = joints.1;
@@ -0,0 +1,266 @@
+use bevy_ecs_ldtk::prelude::*;
+use bevy_simple_prefs::Prefs;
+use bevy_simple_prefs::PrefsPlugin;
+
+use crate::game::juice::Pop;
+use crate::game::player::Player;
+use crate::game::run_stats::RunStats;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// Where progress is saved: a file on native, or local storage on web.
+const PROGRESS_FILE: &str = "progress.ron";
+/// Secrets found in an earlier attempt stay in the level, faded out.
+const FOUND_SECRET_ALPHA: f32 = 0.3;
+const OPTIONAL_TINT: Color = Color::srgb(0.5, 0.8, 1.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(CollectibleAssets, FoundSecrets)>();
+    app.register_type::<Collectible>();
+    app.register_type::<CollectibleTally>();
+
+    app.add_plugins(PrefsPlugin::<SavedProgress> {
+        filename: PROGRESS_FILE.to_string(),
+        ..default()
+    });
+
+    app.register_ldtk_entity::<CollectibleBundle>("box");
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(
+                (
+                    spawn_collectible_tally,
+                    process_collectibles,
+                    sync_run_stats,
+                )
+                    .chain()
+                    .in_set(CollectibleSystems),
+            )
+            .in_set(PausableSystems),
+    );
+}
+
+/// Counts collectibles as they're spawned and collected.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CollectibleSystems;
+
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct CollectibleAssets {
+    #[asset(path = "image/box.png")]
+    box_image: Handle<Image>,
+    #[asset(path = "image/star.png")]
+    star_image: Handle<Image>,
+}
+
+impl Configure for CollectibleAssets {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_collection::<Self>();
+    }
+}
+
+impl CollectibleAssets {
+    /// The HUD icon for a kind of collectible.
+    pub fn icon(&self, kind: CollectibleKind) -> ImageNode {
+        match kind {
+            CollectibleKind::Required => ImageNode::new(self.box_image.clone()),
+            CollectibleKind::Optional => {
+                ImageNode::new(self.box_image.clone()).with_color(OPTIONAL_TINT)
+            },
+            CollectibleKind::Secret => ImageNode::new(self.star_image.clone()),
+        }
+    }
+}
+
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum CollectibleKind {
+    /// Every one has to be collected to open the goal.
+    #[default]
+    Required,
+    /// Only worth points.
+    Optional,
+    /// Stays collected across deaths and sessions once found.
+    Secret,
+}
+
+impl CollectibleKind {
+    pub const ALL: [Self; 3] = [Self::Required, Self::Optional, Self::Secret];
+}
+
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[reflect(Component)]
+pub struct Collectible {
+    pub kind: CollectibleKind,
+    pub points: u32,
+}
+
+impl Collectible {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        let kind = match entity_instance.get_enum_field("kind").map(String::as_str) {
+            Ok("Optional") => CollectibleKind::Optional,
+            Ok("Secret") => CollectibleKind::Secret,
+            _ => CollectibleKind::Required,
+        };
+        let points = entity_instance
+            .get_int_field("points")
+            .map_or(1, |&x| x.max(0) as u32);
+
+        Self { kind, points }
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct CollectibleBundle {
+    #[with(Collectible::from_entity_instance)]
+    collectible: Collectible,
+    #[sprite_sheet]
+    sprite: Sprite,
+
+    rigid_body: RigidBody,
+    sensor: Sensor,
+    collision_events_enabled: CollisionEventsEnabled,
+}
+
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct CollectibleCount {
+    pub collected: u32,
+    pub total: u32,
+}
+
+impl CollectibleCount {
+    pub fn is_complete(self) -> bool {
+        self.collected >= self.total
+    }
+}
+
+/// How many of each kind of collectible the current level has, and how many have been collected.
+#[derive(Component, Reflect, Clone, Default, Debug)]
+#[reflect(Component)]
+pub struct CollectibleTally {
+    pub required: CollectibleCount,
+    pub optional: CollectibleCount,
+    pub secret: CollectibleCount,
+    /// Points from everything collected so far.
+    pub points: u32,
+}
+
+impl CollectibleTally {
+    pub fn get(&self, kind: CollectibleKind) -> CollectibleCount {
+        match kind {
+            CollectibleKind::Required => self.required,
+            CollectibleKind::Optional => self.optional,
+            CollectibleKind::Secret => self.secret,
+        }
+    }
+
+    fn get_mut(&mut self, kind: CollectibleKind) -> &mut CollectibleCount {
+        match kind {
+            CollectibleKind::Required => &mut self.required,
+            CollectibleKind::Optional => &mut self.optional,
+            CollectibleKind::Secret => &mut self.secret,
+        }
+    }
+
+    /// How many collectibles of any kind have been collected.
+    pub fn total_collected(&self) -> u32 {
+        self.required.collected + self.optional.collected + self.secret.collected
+    }
+
+    fn collect(&mut self, collectible: Collectible) {
+        self.get_mut(collectible.kind).collected += 1;
+        self.points += collectible.points;
+    }
+}
+
+/// Progress that's kept across sessions.
+#[derive(Prefs, Reflect, Default)]
+struct SavedProgress {
+    found_secrets: FoundSecrets,
+}
+
+/// Secrets that have been found, by LDtk IID.
+#[derive(Resource, Reflect, Clone, PartialEq, Eq, Default, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct FoundSecrets(pub Vec<String>);
+
+impl Configure for FoundSecrets {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl FoundSecrets {
+    fn contains(&self, iid: &EntityIid) -> bool {
+        self.0.iter().any(|x| x == iid.as_str())
+    }
+}
+
+fn spawn_collectible_tally(mut commands: Commands, level_entity: Single<Entity, Added<LevelIid>>) {
+    commands
+        .entity(*level_entity)
+        .with_child(CollectibleTally::default());
+}
+
+fn process_collectibles(
+    mut commands: Commands,
+    collectible_query: Query<(Entity, &Collectible, &EntityIid, &mut Sprite), Added<Collectible>>,
+    mut tally: Single<&mut CollectibleTally>,
+    found_secrets: Res<FoundSecrets>,
+) {
+    for (entity, &collectible, iid, mut sprite) in collectible_query {
+        tally.get_mut(collectible.kind).total += 1;
+
+        if collectible.kind == CollectibleKind::Secret && found_secrets.contains(iid) {
+            tally.collect(collectible);
+            sprite.color.set_alpha(FOUND_SECRET_ALPHA);
+            continue;
+        }
+        if collectible.kind == CollectibleKind::Optional {
+            sprite.color = OPTIONAL_TINT;
+        }
+
+        commands
+            .entity(entity)
+            .insert(Collider::rectangle(15.0, 15.0))
+            .observe(on_collect);
+    }
+}
+
+fn on_collect(
+    trigger: Trigger<OnCollisionStart>,
+    player_query: Query<Entity, With<Player>>,
+    collectible_query: Query<(&Collectible, &EntityIid)>,
+    mut tally: Single<&mut CollectibleTally>,
+    mut found_secrets: ResMut<FoundSecrets>,
+    mut commands: Commands,
+) {
+    if !player_query.contains(trigger.collider) {
+        return;
+    }
+    let entity = trigger.target();
+    let (&collectible, iid) = r!(collectible_query.get(entity));
+
+    tally.collect(collectible);
+    // Stop it being collected again while it pops away.
+    commands
+        .entity(entity)
+        .remove::<(RigidBody, Collider)>()
+        .insert(Pop::default());
+
+    // Changes are saved automatically.
+    if collectible.kind == CollectibleKind::Secret {
+        found_secrets.push(iid.as_str().to_string());
+    }
+}
+
+fn sync_run_stats(
+    tally: Single<&CollectibleTally, Changed<CollectibleTally>>,
+    level_selection: Res<LevelSelection>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    run_stats.level_mut(&level_selection).collectibles = tally.clone();
+}
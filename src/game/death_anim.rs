@@ -1,15 +1,21 @@
-use bevy::asset::RenderAssetUsages;
-use bevy::render::mesh::Indices;
-use bevy::render::mesh::PrimitiveTopology;
-
+use crate::game::health::Difficulty;
+use crate::game::health::Lives;
 use crate::game::player::Player;
-use crate::game::player::PlayerEye;
 use crate::menu::Menu;
 use crate::prelude::*;
 use crate::screen::Screen;
+use crate::screen::fade::fade_out;
 use crate::screen::gameplay::ShowPlayerDeathMenu;
 
+const DYING_DURATION: f32 = 3.0;
+const QUICK_RESPAWN_DYING_DURATION: f32 = 0.6;
+/// How fast the ragdoll is thrown away from the hazard.
+const RAGDOLL_SPEED: f32 = 400.0;
+const RAGDOLL_SPIN: f32 = 12.0;
+
 pub(super) fn plugin(app: &mut App) {
+    app.configure::<DeathSettings>();
+
     app.add_event::<PlayerDeath>();
 
     app.add_state::<PlayerDying>();
@@ -18,8 +24,14 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         PauseWhenDyingSystems.run_if(PlayerDying::is_disabled),
     );
+    app.configure_sets(
+        FixedUpdate,
+        PauseWhenDyingSystems.run_if(PlayerDying::is_disabled),
+    );
 
     app.add_systems(StateFlush, Menu::Death.on_enter(reset_death));
+    // Quick respawns restart the level without going through the death menu.
+    app.add_systems(StateFlush, Screen::Gameplay.on_exit(reset_death));
 
     app.add_systems(
         Update,
@@ -29,8 +41,30 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-#[derive(Event, Debug, Clone, Copy, PartialEq, Default)]
-pub struct PlayerDeath;
+/// How the player dies and comes back.
+#[derive(Resource, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct DeathSettings {
+    /// Respawn straight after a short death animation instead of showing the death menu.
+    pub quick_respawn: bool,
+    /// Let the player tumble away from whatever killed them.
+    pub ragdoll: bool,
+}
+
+impl Configure for DeathSettings {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+#[derive(Event, Reflect, Debug, Clone, PartialEq)]
+pub struct PlayerDeath {
+    pub source: Entity,
+    pub cause: Name,
+    /// Where the killing hit came from.
+    pub position: Vec2,
+}
 
 #[derive(Component, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut, Reflect)]
 #[reflect(Component)]
@@ -46,65 +80,62 @@ pub struct PauseWhenDyingSystems;
 fn handle_player_death(
     mut player_death_event_reader: EventReader<PlayerDeath>,
     mut player_dying: NextMut<PlayerDying>,
-    player_entity: Single<Entity, With<Player>>,
+    settings: Res<DeathSettings>,
+    player: Single<(Entity, &GlobalTransform), With<Player>>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    player_eye_query: Query<Entity, With<PlayerEye>>,
 ) {
-    if let Some(_death_event) = player_death_event_reader.read().last()
+    if let Some(death_event) = player_death_event_reader.read().last()
         && player_dying.get().is_none()
     {
         player_dying.enable_default();
 
+        let (player_entity, transform) = *player;
+        let duration = if settings.quick_respawn {
+            QUICK_RESPAWN_DYING_DURATION
+        } else {
+            DYING_DURATION
+        };
         commands
-            .entity(*player_entity)
-            .insert(DyingTimer(Timer::from_seconds(3.0, TimerMode::Once)));
-
-        let new_eye_mesh = meshes.add(generate_cross_mesh());
-        for eye_entity in player_eye_query {
-            commands
-                .entity(eye_entity)
-                .insert(Mesh2d(new_eye_mesh.clone()));
+            .entity(player_entity)
+            .insert(DyingTimer(Timer::from_seconds(duration, TimerMode::Once)));
+
+        if settings.ragdoll {
+            // Throw the player up and away from the hazard, spinning as it goes.
+            let away = (transform.translation().xy() - death_event.position).normalize_or(Vec2::Y);
+            let velocity = (away + Vec2::Y).normalize() * RAGDOLL_SPEED;
+            let spin = if away.x < 0.0 {
+                RAGDOLL_SPIN
+            } else {
+                -RAGDOLL_SPIN
+            };
+            commands.entity(player_entity).insert((
+                LockedAxes::new(),
+                LinearVelocity(velocity),
+                AngularVelocity(spin),
+            ));
         }
     }
 }
 
-// <https://bevy.org/examples/2d-rendering/mesh2d-manual/>
-// adapted to make a cross which is two diagonal rectangles
-fn generate_cross_mesh() -> Mesh {
-    let mut cross_mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::RENDER_WORLD,
-    );
-
-    let vertices_positions = vec![
-        [-10.0, 6.0, 0.0],
-        [-6.0, 10.0, 0.0],
-        [10.0, -6.0, 0.0],
-        [6.0, -10.0, 0.0],
-        [6.0, 10.0, 0.0],
-        [10.0, 6.0, 0.0],
-        [-6.0, -10.0, 0.0],
-        [-10.0, -6.0, 0.0],
-    ];
-
-    cross_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices_positions);
-
-    let triangle_indices = vec![0, 1, 2, 2, 3, 0, 4, 5, 6, 6, 7, 4];
-    cross_mesh.insert_indices(Indices::U32(triangle_indices));
-
-    cross_mesh
-}
-
 fn handle_player_death_timer(
+    mut commands: Commands,
     time: Res<Time>,
+    settings: Res<DeathSettings>,
+    difficulty: Res<Difficulty>,
+    lives: Res<Lives>,
     dying_timer_query: Query<&mut DyingTimer>,
     mut player_death_menu: NextMut<ShowPlayerDeathMenu>,
 ) {
     for mut timer in dying_timer_query {
         timer.tick(time.delta());
+        if !timer.just_finished() {
+            continue;
+        }
 
-        if timer.just_finished() {
+        // Running out of lives always goes to the menu, so the run can be restarted.
+        if settings.quick_respawn && !(difficulty.has_lives() && lives.0 == 0) {
+            commands.spawn(fade_out(Screen::Gameplay));
+        } else {
             player_death_menu.enable_default();
         }
     }
@@ -0,0 +1,222 @@
+//! Conversations with NPCs, loaded from `.dialogue.ron` files and shown in [`Menu::Dialogue`].
+
+use bevy_common_assets::ron::RonAssetPlugin;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::death_anim::PauseWhenDyingSystems;
+use crate::game::player::Player;
+use crate::game::player::PlayerAction;
+use crate::game::timeline::PlayerInputLocked;
+use crate::game::world_text::ProximityFade;
+use crate::game::world_text::WorldText;
+use crate::menu::Menu;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// How close the player has to be to an NPC to talk to them.
+const TALK_RANGE: f32 = 128.0;
+/// Where the prompt to talk is shown, relative to the NPC.
+const TALK_PROMPT_OFFSET: Vec3 = Vec3::new(0.0, 64.0, 1.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Npc>();
+
+    app.configure::<(DialogueFlags, ActiveDialogue)>();
+
+    app.add_plugins(RonAssetPlugin::<Dialogue>::new(&["dialogue.ron"]));
+
+    app.register_ldtk_entity::<NpcBundle>("npc");
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay.on_update((
+            process_npcs,
+            talk_to_npc
+                .in_set(UpdateSystems::RecordInput)
+                .in_set(PausableSystems)
+                .in_set(PauseWhenDyingSystems)
+                .run_if(
+                    PlayerInputLocked::is_disabled.and(action_just_pressed(PlayerAction::Interact)),
+                ),
+        )),
+    );
+}
+
+/// A conversation, split into pages that are revealed a character at a time.
+#[derive(Asset, Reflect, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Dialogue {
+    /// Who's talking, shown above the text.
+    pub speaker: String,
+    /// An image of the speaker, by its path in the assets folder.
+    #[serde(default)]
+    pub portrait: Option<String>,
+    /// How many characters are revealed per second.
+    pub speed: f32,
+    pub pages: Vec<DialoguePage>,
+}
+
+impl Dialogue {
+    /// The first page from `page` onwards that isn't waiting on a flag.
+    pub fn next_page(&self, page: usize, flags: &DialogueFlags) -> Option<usize> {
+        (page..self.pages.len()).find(|&i| {
+            self.pages[i]
+                .requires
+                .as_ref()
+                .is_none_or(|flag| flags.contains(flag))
+        })
+    }
+
+    /// The page to show after `page`, or `None` if the dialogue is over.
+    pub fn follow(&self, page: usize, next: DialogueNext, flags: &DialogueFlags) -> Option<usize> {
+        match next {
+            DialogueNext::Next => self.next_page(page + 1, flags),
+            DialogueNext::Page(i) => self.next_page(i, flags),
+            DialogueNext::End => None,
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DialoguePage {
+    /// Rich text, with the same markup as labels.
+    pub text: String,
+    /// Skips the page unless a choice has set this flag.
+    #[serde(default)]
+    pub requires: Option<String>,
+    /// Answers to pick from once the text is revealed. Without any, the page moves on to the
+    /// next one when the player interacts.
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueChoice {
+    pub text: String,
+    /// A flag to set when the choice is picked.
+    #[serde(default)]
+    pub sets: Option<String>,
+    #[serde(default)]
+    pub then: DialogueNext,
+}
+
+/// Where a dialogue goes after a choice is picked.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DialogueNext {
+    /// The next page.
+    #[default]
+    Next,
+    /// A page by its index.
+    Page(usize),
+    End,
+}
+
+/// Flags set by dialogue choices over the course of a run.
+#[derive(Resource, Reflect, Default, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct DialogueFlags(pub HashSet<String>);
+
+impl Configure for DialogueFlags {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        // Every run starts from the title screen.
+        app.add_systems(StateFlush, Screen::Title.on_enter(reset_dialogue_flags));
+    }
+}
+
+fn reset_dialogue_flags(mut flags: ResMut<DialogueFlags>) {
+    *flags = default();
+}
+
+/// The dialogue being shown in [`Menu::Dialogue`].
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct ActiveDialogue {
+    pub dialogue: Handle<Dialogue>,
+    pub page: usize,
+    /// How many characters of the page have been revealed so far.
+    pub revealed: f32,
+}
+
+impl Configure for ActiveDialogue {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+/// Someone the player can talk to.
+///
+/// Expects the LDtk entity to have a `dialogue` string field with the path to a `.dialogue.ron`
+/// file in the assets folder.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct Npc {
+    pub dialogue: String,
+    handle: Handle<Dialogue>,
+}
+
+impl Npc {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        Self {
+            dialogue: entity_instance
+                .get_string_field("dialogue")
+                .cloned()
+                .unwrap_or_default(),
+            ..default()
+        }
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct NpcBundle {
+    #[with(Npc::from_entity_instance)]
+    npc: Npc,
+    #[sprite_sheet]
+    sprite: Sprite,
+}
+
+fn process_npcs(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut npc_query: Query<(Entity, &mut Npc), Added<Npc>>,
+) {
+    for (entity, mut npc) in &mut npc_query {
+        npc.handle = asset_server.load(&npc.dialogue);
+        commands
+            .entity(entity)
+            .insert(Name::new("Npc"))
+            .with_child((
+                Name::new("TalkPrompt"),
+                WorldText("[b]{action:Interact}[r] to talk".to_string()),
+                ProximityFade::new(TALK_RANGE),
+                Transform::from_translation(TALK_PROMPT_OFFSET),
+            ));
+    }
+}
+
+fn talk_to_npc(
+    player: Single<&GlobalTransform, With<Player>>,
+    npc_query: Query<(&Npc, &GlobalTransform)>,
+    dialogues: Res<Assets<Dialogue>>,
+    flags: Res<DialogueFlags>,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    mut menu: NextMut<Menu>,
+) {
+    let player_pos = player.translation().xy();
+    let npc = rq!(npc_query.iter().find_map(|(npc, transform)| {
+        (transform.translation().xy().distance(player_pos) <= TALK_RANGE).then_some(npc)
+    }));
+    let dialogue = r!(dialogues.get(&npc.handle));
+    let page = rq!(dialogue.next_page(0, &flags));
+
+    *active_dialogue = ActiveDialogue {
+        dialogue: npc.handle.clone(),
+        page,
+        revealed: 0.0,
+    };
+    menu.enter(Menu::Dialogue);
+}
@@ -0,0 +1,298 @@
+//! Reusable enemy behaviours: patrolling, noticing the player, and attacking on a cooldown.
+
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::chain_movement::GameLayer;
+use crate::game::death_anim::PauseWhenDyingSystems;
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::util::trajectory;
+
+/// The grid size of the LDtk entities layer, which patrol points are placed on.
+const ENTITY_GRID_SIZE: f32 = 32.0;
+const DEFAULT_DETECTION_RANGE: f32 = 250.0;
+const DEFAULT_ATTACK_COOLDOWN: f32 = 5.0;
+const PATROL_SPEED: f32 = 40.0;
+const DEFAULT_ACCURACY: f32 = 0.8;
+const DEFAULT_LEAD: f32 = 0.5;
+/// How far off a throw can be, in radians, with no accuracy at all.
+const MAX_AIM_ERROR: f32 = 0.35;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(Enemy, Patrol, Detection, AttackCooldown, Aim)>();
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(
+                (
+                    process_patrols,
+                    detect_player,
+                    update_patrols,
+                    attack_player,
+                )
+                    .chain()
+                    .in_set(EnemySystems),
+            )
+            .in_set(PausableSystems)
+            .in_set(PauseWhenDyingSystems),
+    );
+}
+
+/// Moves enemies and decides when they attack.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct EnemySystems;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Enemy;
+
+/// The behaviours shared by every enemy, configured from LDtk.
+///
+/// Expects the LDtk entity to have `range`, `cooldown`, `accuracy` and `lead` float fields and a
+/// `patrol` point array.
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct EnemyBundle {
+    enemy: Enemy,
+    #[with(Patrol::from_entity_instance)]
+    patrol: Patrol,
+    #[with(Detection::from_entity_instance)]
+    detection: Detection,
+    #[with(AttackCooldown::from_entity_instance)]
+    attack_cooldown: AttackCooldown,
+    #[with(Aim::from_entity_instance)]
+    aim: Aim,
+}
+
+/// Walks between points, then back to where the enemy was placed, and repeats.
+#[derive(Component, Reflect, Clone, Default, Debug)]
+#[reflect(Component)]
+pub struct Patrol {
+    /// Offsets from the spawn position. An empty route means the enemy stays put.
+    pub route: Vec<Vec2>,
+    pub speed: f32,
+    origin: Vec2,
+    /// The waypoint being walked towards, where 0 is the spawn position.
+    next: usize,
+}
+
+impl Patrol {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        let route = entity_instance
+            .get_points_field("patrol")
+            .map(|points| {
+                points
+                    .iter()
+                    .map(|&point| {
+                        // LDtk grid coordinates point down, unlike world coordinates.
+                        let cells = point - entity_instance.grid;
+                        Vec2::new(cells.x as f32, -cells.y as f32) * ENTITY_GRID_SIZE
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            route,
+            speed: PATROL_SPEED,
+            ..default()
+        }
+    }
+
+    fn waypoint(&self) -> Vec2 {
+        match self.next {
+            0 => self.origin,
+            i => self.origin + self.route[i - 1],
+        }
+    }
+}
+
+/// Notices the player when they're within range and not hidden behind a wall.
+#[derive(Component, Reflect, Copy, Clone, Default, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct Detection {
+    pub range: f32,
+    pub sees_player: bool,
+    /// Where the player was last seen.
+    pub target: Vec2,
+    /// How fast the player was moving when last seen.
+    pub target_velocity: Vec2,
+}
+
+impl Detection {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        Self {
+            range: entity_instance
+                .get_float_field("range")
+                .map_or(DEFAULT_DETECTION_RANGE, |&x| x),
+            ..default()
+        }
+    }
+}
+
+/// Triggers [`EnemyAttack`] whenever the timer has finished and the player is in sight.
+#[derive(Component, Reflect, Clone, Default, PartialEq, Eq, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct AttackCooldown(pub Timer);
+
+impl AttackCooldown {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        let cooldown = entity_instance
+            .get_float_field("cooldown")
+            .map_or(DEFAULT_ATTACK_COOLDOWN, |&x| x);
+
+        Self(Timer::from_seconds(cooldown, TimerMode::Once))
+    }
+}
+
+/// How well an enemy throws things at the player.
+#[derive(Component, Reflect, Copy, Clone, Default, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct Aim {
+    /// From 0 to 1, where 1 never misses a player standing still.
+    pub accuracy: f32,
+    /// From 0 to 1, how far ahead of a moving player to aim.
+    pub lead: f32,
+}
+
+impl Aim {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        Self {
+            accuracy: entity_instance
+                .get_float_field("accuracy")
+                .map_or(DEFAULT_ACCURACY, |&x| x.clamp(0.0, 1.0)),
+            lead: entity_instance
+                .get_float_field("lead")
+                .map_or(DEFAULT_LEAD, |&x| x.clamp(0.0, 1.0)),
+        }
+    }
+
+    /// The velocity to throw something at a fixed speed from `start` to hit the target of an attack.
+    ///
+    /// Targets that are out of range get the furthest-reaching throw towards them instead.
+    pub fn launch_velocity(
+        &self,
+        start: Vec2,
+        attack: &EnemyAttack,
+        speed: f32,
+        gravity: Vec2,
+    ) -> Vec2 {
+        let mut target = attack.target;
+        if let Some(velocity) = trajectory::velocity_for_speed(start, target, gravity, speed, false)
+        {
+            let time = trajectory::time_to_reach(start, target, velocity, gravity);
+            target += attack.target_velocity * time * self.lead;
+        }
+
+        let velocity = trajectory::velocity_for_speed(start, target, gravity, speed, false)
+            .unwrap_or_else(|| trajectory::furthest_velocity(start, target, gravity, speed));
+        let error = (1.0 - self.accuracy) * MAX_AIM_ERROR * (2.0 * random::<f32>() - 1.0);
+
+        Vec2::from_angle(error).rotate(velocity)
+    }
+}
+
+/// Triggered on an enemy when it attacks, so each kind of enemy can decide what the attack is.
+#[derive(Event, Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct EnemyAttack {
+    /// Where the player was seen.
+    pub target: Vec2,
+    /// How fast the player was moving when seen.
+    pub target_velocity: Vec2,
+}
+
+/// Whether nothing on the wall layer is between two points.
+pub fn has_line_of_sight(spatial_query: &SpatialQuery, from: Vec2, to: Vec2) -> bool {
+    let Ok((direction, distance)) = Dir2::new_and_length(to - from) else {
+        return true;
+    };
+    let filter = SpatialQueryFilter::from_mask(GameLayer::WallLayer);
+    spatial_query
+        .cast_ray(from, direction, distance, true, &filter)
+        .is_none()
+}
+
+fn process_patrols(
+    mut commands: Commands,
+    mut patrol_query: Query<(Entity, &mut Patrol, &Transform), Added<Patrol>>,
+) {
+    for (entity, mut patrol, transform) in &mut patrol_query {
+        patrol.origin = transform.translation.xy();
+        if !patrol.route.is_empty() {
+            commands.entity(entity).insert(RigidBody::Kinematic);
+        }
+    }
+}
+
+fn detect_player(
+    spatial_query: SpatialQuery,
+    player_query: Query<(&GlobalTransform, &LinearVelocity), With<Player>>,
+    mut detection_query: Query<(&mut Detection, &GlobalTransform)>,
+) {
+    let player = player_query.single().ok();
+    let player_pos = player.map(|(gt, _)| gt.translation().xy());
+
+    for (mut detection, transform) in &mut detection_query {
+        let pos = transform.translation().xy();
+        let sees_player = player_pos.is_some_and(|player_pos| {
+            pos.distance(player_pos) <= detection.range
+                && has_line_of_sight(&spatial_query, pos, player_pos)
+        });
+
+        detection.sees_player = sees_player;
+        if let Some((gt, velocity)) = player.filter(|_| sees_player) {
+            detection.target = gt.translation().xy();
+            detection.target_velocity = velocity.0;
+        }
+    }
+}
+
+fn update_patrols(
+    time: Res<Time>,
+    mut patrol_query: Query<(
+        &mut Patrol,
+        &Transform,
+        &mut LinearVelocity,
+        Option<&Detection>,
+    )>,
+) {
+    for (mut patrol, transform, mut velocity, detection) in &mut patrol_query {
+        if patrol.route.is_empty() {
+            continue;
+        }
+        // Stand still to attack while the player is in sight.
+        if detection.is_some_and(|x| x.sees_player) {
+            velocity.0 = Vec2::ZERO;
+            continue;
+        }
+
+        let delta = patrol.waypoint() - transform.translation.xy();
+        if delta.length() <= patrol.speed * time.delta_secs() {
+            patrol.next = (patrol.next + 1) % (patrol.route.len() + 1);
+        }
+        velocity.0 = delta.normalize_or_zero() * patrol.speed;
+    }
+}
+
+fn attack_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut enemy_query: Query<(Entity, &mut AttackCooldown, &Detection)>,
+) {
+    for (entity, mut cooldown, detection) in &mut enemy_query {
+        cooldown.tick(time.delta());
+        if !detection.sees_player || !cooldown.finished() {
+            continue;
+        }
+
+        cooldown.reset();
+        commands.trigger_targets(
+            EnemyAttack {
+                target: detection.target,
+                target_velocity: detection.target_velocity,
+            },
+            entity,
+        );
+    }
+}
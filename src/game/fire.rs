@@ -0,0 +1,488 @@
+//! Fire that spreads as a cellular automaton over the level's grid.
+
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::core::audio::AudioSettings;
+use crate::core::audio::sfx_audio;
+use crate::game::animated_sprite::AnimationFrameEvent;
+use crate::game::animated_sprite::SpriteAnimation;
+use crate::game::aseprite::AsepriteSheet;
+use crate::game::vines::on_collision_damages_player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// The LDtk layer that sets the fire grid's size and resolution.
+const GRID_LAYER: &str = "walls";
+/// How often fire burns down and spreads, in seconds.
+const FIRE_STEP: f32 = 0.25;
+/// Burn times are scaled by a random factor in this range, so fire doesn't pulse.
+const FUEL_VARIATION: (f32, f32) = (0.75, 1.25);
+/// Fire set directly in a cell burns for at least this long, so it shows up even in air.
+const SPARK_FUEL: f32 = 0.75;
+/// How likely fire is to jump a one-cell gap between plants, relative to catching normally.
+const GAP_JUMP_CHANCE: f32 = 0.25;
+/// How much bigger a fire sprite is than its cell, so neighbouring fires overlap.
+const FIRE_SPRITE_SCALE: f32 = 1.5;
+const FIRE_Z: f32 = 100.0;
+/// Burning entities are tinted this colour.
+const BURNING_TINT: Color = Color::srgb(1.0, 0.45, 0.2);
+/// How much of its cell a fire hurts the player in, since the sprite doesn't fill its bounds.
+const FIRE_HITBOX_SCALE: f32 = 0.6;
+/// A big fire flares every frame somewhere, so the flare sound plays at most this often.
+const FLARE_SOUND_INTERVAL: f32 = 0.3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<FireAssets>();
+    app.register_type::<FireGrid>();
+    app.register_type::<FireSource>();
+    app.register_type::<Flammable>();
+    app.register_type::<OnFire>();
+
+    app.register_ldtk_entity::<FireSourceBundle>("fire");
+
+    app.add_event::<IgniteFire>();
+    app.add_event::<FireCellBurntOut>();
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update((
+                (
+                    build_fire_grid,
+                    light_fire_sources,
+                    ignite_fire,
+                    ignite_flammables,
+                    burn_flammables,
+                    step_fire,
+                )
+                    .chain(),
+                play_flare_sounds,
+            ))
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct FireAssets {
+    #[asset(path = "image/fire_spritesheet.aseprite.json")]
+    fire_spritesheet: Handle<AsepriteSheet>,
+    #[asset(path = "audio/sfx/251390__deadsillyrabbit__button_hover-mp3.ogg")]
+    flare_sound: Handle<AudioSource>,
+}
+
+impl Configure for FireAssets {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_collection::<Self>();
+    }
+}
+
+/// Sets the grid cell at a world position alight, unless it's a wall.
+#[derive(Event, Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct IgniteFire {
+    pub position: Vec2,
+}
+
+/// Sent when a grid cell has finished burning.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct FireCellBurntOut {
+    pub coords: GridCoords,
+    /// The cell's centre, relative to the level, for layers with a different grid size.
+    pub position: Vec2,
+}
+
+/// A fire placed in the level that never burns out.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct FireSource;
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct FireSourceBundle {
+    fire_source: FireSource,
+}
+
+/// Catches fire from burning cells it overlaps, then burns for `fuel` seconds.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Flammable {
+    pub fuel: f32,
+    pub half_size: Vec2,
+}
+
+/// Counts down until a [`Flammable`] entity burns out.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct OnFire(pub Timer);
+
+/// Triggered on a [`Flammable`] entity when it has burnt out.
+#[derive(Event, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BurntOut;
+
+/// What a grid cell is made of, which decides how it burns.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum FireMaterial {
+    Air,
+    Wall,
+    Bush,
+    Vines,
+}
+
+impl FireMaterial {
+    /// The intgrid layer that sets each material, from highest priority to lowest.
+    const LAYERS: [(&'static str, Self); 3] = [
+        ("walls", FireMaterial::Wall),
+        ("vines", FireMaterial::Vines),
+        ("bush", FireMaterial::Bush),
+    ];
+
+    /// The chance of catching fire from each burning neighbour, every step.
+    fn flammability(self) -> f32 {
+        match self {
+            FireMaterial::Air => 0.0,
+            FireMaterial::Wall => 0.0,
+            FireMaterial::Bush => 0.6,
+            FireMaterial::Vines => 0.8,
+        }
+    }
+
+    /// How long the cell burns for once lit, in seconds.
+    fn fuel(self) -> f32 {
+        match self {
+            FireMaterial::Air => 0.0,
+            FireMaterial::Wall => 0.0,
+            FireMaterial::Bush => 4.0,
+            FireMaterial::Vines => 2.0,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+enum FireCell {
+    Unburnt,
+    Burning { remaining: f32, sprite: Entity },
+    BurntOut,
+}
+
+/// The fire state of every cell in a level, with `(0, 0)` in the bottom left like [`GridCoords`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct FireGrid {
+    width: i32,
+    height: i32,
+    grid_size: f32,
+    materials: Vec<FireMaterial>,
+    cells: Vec<FireCell>,
+    step: Timer,
+}
+
+impl FireGrid {
+    fn index(&self, coords: GridCoords) -> Option<usize> {
+        ((0..self.width).contains(&coords.x) && (0..self.height).contains(&coords.y))
+            .then(|| (coords.y * self.width + coords.x) as usize)
+    }
+
+    /// The cell at a position relative to the level.
+    fn index_at(&self, local: Vec2) -> Option<usize> {
+        let coords = (local / self.grid_size).floor().as_ivec2();
+        self.index(GridCoords::new(coords.x, coords.y))
+    }
+
+    /// Any burning cell that overlaps a rectangle relative to the level.
+    fn burning_in(&self, center: Vec2, half_size: Vec2) -> Option<usize> {
+        let min = ((center - half_size) / self.grid_size).floor().as_ivec2();
+        let max = ((center + half_size) / self.grid_size).floor().as_ivec2();
+        (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| GridCoords::new(x, y)))
+            .filter_map(|coords| self.index(coords))
+            .find(|&i| self.is_burning(i))
+    }
+
+    fn coords(&self, index: usize) -> GridCoords {
+        GridCoords::new(index as i32 % self.width, index as i32 / self.width)
+    }
+
+    /// The position of a cell's centre, relative to the level.
+    fn cell_center(&self, index: usize) -> Vec2 {
+        let coords = self.coords(index);
+        (Vec2::new(coords.x as f32, coords.y as f32) + 0.5) * self.grid_size
+    }
+
+    /// The cell `steps` cells away from a cell in a direction.
+    fn offset(&self, index: usize, direction: IVec2, steps: i32) -> Option<usize> {
+        let coords = self.coords(index);
+        let offset = direction * steps;
+        self.index(GridCoords::new(coords.x + offset.x, coords.y + offset.y))
+    }
+
+    fn is_burning(&self, index: usize) -> bool {
+        matches!(self.cells[index], FireCell::Burning { .. })
+    }
+
+    /// How long a cell burns for, with some variation.
+    fn fuel(&self, index: usize) -> f32 {
+        let (min, max) = FUEL_VARIATION;
+        self.materials[index].fuel() * thread_rng().gen_range(min..max)
+    }
+
+    fn ignite(
+        &mut self,
+        index: usize,
+        fuel: f32,
+        level_entity: Entity,
+        commands: &mut Commands,
+        sheet: &AsepriteSheet,
+    ) {
+        let material = self.materials[index];
+        if material == FireMaterial::Wall || self.cells[index] != FireCell::Unburnt {
+            return;
+        }
+
+        let mut sprite = sheet.sprite();
+        sprite.custom_size = Some(Vec2::splat(self.grid_size * FIRE_SPRITE_SCALE));
+        let hitbox = self.grid_size * FIRE_HITBOX_SCALE;
+        let sprite = commands
+            .spawn((
+                Name::new("Fire"),
+                sprite,
+                SpriteAnimation::new(sheet.clips.clone(), "burn"),
+                Transform::from_translation(self.cell_center(index).extend(FIRE_Z)),
+                ChildOf(level_entity),
+                RigidBody::Static,
+                Sensor,
+                CollisionEventsEnabled,
+                Collider::rectangle(hitbox, hitbox),
+            ))
+            .observe(on_collision_damages_player)
+            .id();
+
+        self.cells[index] = FireCell::Burning {
+            remaining: fuel,
+            sprite,
+        };
+    }
+}
+
+fn build_fire_grid(
+    mut commands: Commands,
+    level_query: Query<(Entity, &LevelIid), Added<LevelIid>>,
+    ldtk_project: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    for (level_entity, level_iid) in &level_query {
+        let project = c!(ldtk_project_assets.get(*ldtk_project));
+        let level = c!(project
+            .as_standalone()
+            .get_loaded_level_by_iid(&level_iid.to_string()));
+        let layers = level.layer_instances();
+        let grid_layer = c!(layers.iter().find(|x| x.identifier == GRID_LAYER));
+        let (width, height, grid_size) = (grid_layer.c_wid, grid_layer.c_hei, grid_layer.grid_size);
+
+        let mut materials = vec![FireMaterial::Air; (width * height) as usize];
+        // Go from lowest priority to highest, so higher priority layers win.
+        for (identifier, material) in FireMaterial::LAYERS.into_iter().rev() {
+            let layer = cq!(layers.iter().find(|x| x.identifier == identifier));
+            for (i, &value) in layer.int_grid_csv.iter().enumerate() {
+                cq!(value != 0);
+
+                // LDtk rows go from the top down, and layers may use a different grid size.
+                let (x, row) = (i as i32 % layer.c_wid, i as i32 / layer.c_wid);
+                let scale = layer.grid_size / grid_size;
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let (x, row) = (x * scale + dx, row * scale + dy);
+                        cq!(x < width && row < height);
+                        materials[((height - 1 - row) * width + x) as usize] = material;
+                    }
+                }
+            }
+        }
+
+        commands.entity(level_entity).insert(FireGrid {
+            width,
+            height,
+            grid_size: grid_size as f32,
+            cells: vec![FireCell::Unburnt; materials.len()],
+            materials,
+            step: Timer::from_seconds(FIRE_STEP, TimerMode::Repeating),
+        });
+    }
+}
+
+/// Converts a world position to a position relative to the level.
+fn to_level(level_transform: &GlobalTransform, position: Vec2) -> Vec2 {
+    level_transform
+        .affine()
+        .inverse()
+        .transform_point3(position.extend(0.0))
+        .xy()
+}
+
+fn light_fire_sources(
+    mut commands: Commands,
+    assets: Res<FireAssets>,
+    sheets: Res<Assets<AsepriteSheet>>,
+    source_query: Query<(&Transform, &ChildOf), With<FireSource>>,
+    parent_query: Query<&ChildOf>,
+    mut grid_query: Query<&mut FireGrid>,
+) {
+    let sheet = rq!(sheets.get(&assets.fire_spritesheet));
+    for (transform, child_of) in &source_query {
+        // An entity's direct parent is its layer, which sits at the level's origin.
+        let level_entity = c!(parent_query.get(child_of.parent())).parent();
+        let mut grid = cq!(grid_query.get_mut(level_entity));
+        let grid = &mut *grid;
+        let index = c!(grid.index_at(transform.translation.xy()));
+
+        match &mut grid.cells[index] {
+            FireCell::Burning { remaining, .. } => *remaining = f32::INFINITY,
+            cell => {
+                *cell = FireCell::Unburnt;
+                grid.ignite(index, f32::INFINITY, level_entity, &mut commands, sheet);
+            },
+        }
+    }
+}
+
+fn ignite_fire(
+    mut commands: Commands,
+    mut ignite_event_reader: EventReader<IgniteFire>,
+    assets: Res<FireAssets>,
+    sheets: Res<Assets<AsepriteSheet>>,
+    mut grid_query: Query<(Entity, &mut FireGrid, &GlobalTransform)>,
+) {
+    let sheet = rq!(sheets.get(&assets.fire_spritesheet));
+    for event in ignite_event_reader.read() {
+        for (level_entity, mut grid, transform) in &mut grid_query {
+            let index = cq!(grid.index_at(to_level(transform, event.position)));
+            let fuel = grid.fuel(index).max(SPARK_FUEL);
+            grid.ignite(index, fuel, level_entity, &mut commands, sheet);
+        }
+    }
+}
+
+fn ignite_flammables(
+    mut commands: Commands,
+    flammable_query: Query<(Entity, &Flammable, &GlobalTransform), Without<OnFire>>,
+    grid_query: Query<(&FireGrid, &GlobalTransform)>,
+) {
+    for (entity, flammable, transform) in &flammable_query {
+        for (grid, level_transform) in &grid_query {
+            let center = to_level(level_transform, transform.translation().xy());
+            cq!(grid.burning_in(center, flammable.half_size).is_some());
+            commands
+                .entity(entity)
+                .insert(OnFire(Timer::from_seconds(flammable.fuel, TimerMode::Once)));
+            break;
+        }
+    }
+}
+
+fn burn_flammables(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut ignite_event_writer: EventWriter<IgniteFire>,
+    mut burning_query: Query<(Entity, &mut OnFire, &GlobalTransform, Option<&mut Sprite>)>,
+) {
+    for (entity, mut on_fire, transform, sprite) in &mut burning_query {
+        on_fire.tick(time.delta());
+        if let Some(mut sprite) = sprite {
+            sprite.color = BURNING_TINT;
+        }
+
+        // Burning entities carry fire with them as they move.
+        ignite_event_writer.write(IgniteFire {
+            position: transform.translation().xy(),
+        });
+
+        if on_fire.finished() {
+            commands
+                .entity(entity)
+                .remove::<(OnFire, Flammable)>()
+                .trigger(BurntOut);
+        }
+    }
+}
+
+fn step_fire(
+    mut commands: Commands,
+    time: Res<Time>,
+    assets: Res<FireAssets>,
+    sheets: Res<Assets<AsepriteSheet>>,
+    mut grid_query: Query<(Entity, &mut FireGrid)>,
+    mut burnt_out_event_writer: EventWriter<FireCellBurntOut>,
+) {
+    let sheet = rq!(sheets.get(&assets.fire_spritesheet));
+    let mut rng = thread_rng();
+    for (level_entity, mut grid) in &mut grid_query {
+        grid.step.tick(time.delta());
+        for _ in 0..grid.step.times_finished_this_tick() {
+            let burning = (0..grid.cells.len())
+                .filter(|&i| grid.is_burning(i))
+                .collect::<Vec<_>>();
+
+            // Spread from every cell that was burning at the start of the step.
+            let mut spread = vec![];
+            for &i in &burning {
+                for direction in [IVec2::NEG_X, IVec2::X, IVec2::NEG_Y, IVec2::Y] {
+                    let neighbour = cq!(grid.offset(i, direction, 1));
+                    let mut target = neighbour;
+                    let mut chance = grid.materials[neighbour].flammability();
+
+                    // Plants can light plants one cell of air away, but fire never spreads through air.
+                    if grid.materials[neighbour] == FireMaterial::Air
+                        && grid.materials[i].flammability() > 0.0
+                    {
+                        target = cq!(grid.offset(i, direction, 2));
+                        chance = grid.materials[target].flammability() * GAP_JUMP_CHANCE;
+                    }
+
+                    if grid.cells[target] == FireCell::Unburnt && rng.r#gen::<f32>() < chance {
+                        spread.push(target);
+                    }
+                }
+            }
+
+            for i in burning {
+                let FireCell::Burning { remaining, sprite } = &mut grid.cells[i] else {
+                    continue;
+                };
+                *remaining -= FIRE_STEP;
+                if *remaining <= 0.0 {
+                    commands.entity(*sprite).despawn();
+                    grid.cells[i] = FireCell::BurntOut;
+                    burnt_out_event_writer.write(FireCellBurntOut {
+                        coords: grid.coords(i),
+                        position: grid.cell_center(i),
+                    });
+                }
+            }
+
+            for i in spread {
+                let fuel = grid.fuel(i);
+                grid.ignite(i, fuel, level_entity, &mut commands, sheet);
+            }
+        }
+    }
+}
+
+fn play_flare_sounds(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut frame_event_reader: EventReader<AnimationFrameEvent>,
+    assets: Res<FireAssets>,
+    audio_settings: Res<AudioSettings>,
+    mut last_played: Local<Option<f32>>,
+) {
+    let mut flared = false;
+    for event in frame_event_reader.read() {
+        flared |= event.event == "flare";
+    }
+    let now = time.elapsed_secs();
+    if !flared || last_played.is_some_and(|x| now - x < FLARE_SOUND_INTERVAL) {
+        return;
+    }
+
+    *last_played = Some(now);
+    commands.spawn(sfx_audio(&audio_settings, assets.flare_sound.clone()));
+}
@@ -1,25 +1,61 @@
 use bevy_ecs_ldtk::prelude::*;
 
+use crate::game::collectible::CollectibleSystems;
+use crate::game::collectible::CollectibleTally;
 use crate::game::death_anim::PauseWhenDyingSystems;
 use crate::game::end_sequence::StartEndSequenceEvent;
 use crate::game::player::Player;
+use crate::game::switch::Switch;
+use crate::game::timeline::Timeline;
+use crate::game::timeline::play_timeline;
 use crate::prelude::*;
 use crate::screen::Screen;
 
+/// How far from the player the goal indicator is drawn.
+const INDICATOR_DISTANCE: f32 = 40.0;
+/// The goal indicator hides once the player is this close to the goal.
+const INDICATOR_HIDE_DISTANCE: f32 = 120.0;
+const INDICATOR_Z: f32 = 50.0;
+
 pub(super) fn plugin(app: &mut App) {
+    app.configure::<GoalAssets>();
+    app.register_type::<Goal>();
+    app.register_type::<GoalUnlock>();
+    app.register_type::<GoalIndicator>();
+
     app.register_ldtk_entity::<GoalBundle>("goal");
 
-    app.add_event::<EnableGoalEvent>();
+    app.add_event::<GoalOpened>();
 
     app.add_systems(
         Update,
         Screen::Gameplay
-            .on_update((process_goals, rotate, update_goal))
+            .on_update((
+                (process_goals, unlock_goals, announce_opened_goals)
+                    .chain()
+                    .after(CollectibleSystems),
+                rotate,
+                update_goal_indicator,
+            ))
             .in_set(PausableSystems)
             .in_set(PauseWhenDyingSystems),
     );
 }
 
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+struct GoalAssets {
+    #[asset(path = "timeline/goal_opened.timeline.ron")]
+    opened_timeline: Handle<Timeline>,
+}
+
+impl Configure for GoalAssets {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_collection::<Self>();
+    }
+}
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Goal;
@@ -32,33 +68,78 @@ struct DisabledGoal;
 #[reflect(Component)]
 struct RotateComponent;
 
+/// What opens a goal, set in LDtk.
+#[derive(Component, Reflect, Clone, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub enum GoalUnlock {
+    /// Every required collectible has been collected.
+    #[default]
+    AllRequired,
+    /// At least this many collectibles of any kind have been collected.
+    Minimum(u32),
+    /// Every linked switch is on, by LDtk IID.
+    Switches(Vec<String>),
+    /// Open from the start, for levels that are pure traversal.
+    Open,
+}
+
+impl GoalUnlock {
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        match entity_instance.get_enum_field("unlock").map(String::as_str) {
+            Ok("Minimum") => Self::Minimum(
+                entity_instance
+                    .get_int_field("minimum")
+                    .map_or(0, |&x| x.max(0) as u32),
+            ),
+            Ok("Switches") => Self::Switches(
+                entity_instance
+                    .get_maybe_entity_refs_field("switches")
+                    .map(|refs| {
+                        refs.iter()
+                            .flatten()
+                            .map(|x| x.entity_iid.clone())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            Ok("Open") => Self::Open,
+            _ => Self::AllRequired,
+        }
+    }
+
+    fn is_met(
+        &self,
+        tally: &CollectibleTally,
+        switch_query: &Query<(&EntityIid, &Switch)>,
+    ) -> bool {
+        match self {
+            Self::AllRequired => tally.required.is_complete(),
+            Self::Minimum(minimum) => tally.total_collected() >= *minimum,
+            Self::Switches(iids) => iids.iter().all(|iid| {
+                switch_query
+                    .iter()
+                    .any(|(switch_iid, switch)| switch_iid.as_str() == iid && switch.on)
+            }),
+            Self::Open => true,
+        }
+    }
+}
+
 #[derive(Bundle, Default, LdtkEntity)]
 pub struct GoalBundle {
     goal: Goal,
+    #[with(GoalUnlock::from_entity_instance)]
+    unlock: GoalUnlock,
+    disabled: DisabledGoal,
     sensor: Sensor,
     #[sprite_sheet]
     sprite_sheet: Sprite,
 }
 
-#[derive(Debug, Default, Event)]
-pub struct EnableGoalEvent;
-
-fn update_goal(
-    mut commands: Commands,
-    mut event_reader: EventReader<EnableGoalEvent>,
-    goal_query: Query<(Entity, &mut Sprite), With<Goal>>,
-) {
-    if event_reader.read().last().is_some() {
-        for (goal_entity, mut goal_sprite) in goal_query {
-            goal_sprite.color = Color::default();
-
-            commands
-                .entity(goal_entity)
-                .remove::<DisabledGoal>()
-                .insert(RotateComponent)
-                .observe(goal_observer);
-        }
-    }
+/// Sent when a goal opens during play, rather than being open from the start.
+#[derive(Event, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoalOpened {
+    pub goal: Entity,
 }
 
 fn process_goals(mut commands: Commands, goal_query: Query<(Entity, &mut Sprite), Added<Goal>>) {
@@ -67,8 +148,91 @@ fn process_goals(mut commands: Commands, goal_query: Query<(Entity, &mut Sprite)
 
         commands
             .entity(goal_entity)
-            .insert((Collider::rectangle(10.0, 10.0), CollisionEventsEnabled))
-            .insert(DisabledGoal);
+            .insert((Collider::rectangle(10.0, 10.0), CollisionEventsEnabled));
+    }
+}
+
+fn unlock_goals(
+    mut commands: Commands,
+    goal_query: Query<(Entity, Ref<Goal>, &GoalUnlock, &mut Sprite), With<DisabledGoal>>,
+    tally: Single<&CollectibleTally>,
+    switch_query: Query<(&EntityIid, &Switch)>,
+    mut goal_opened_writer: EventWriter<GoalOpened>,
+) {
+    for (goal_entity, goal, unlock, mut goal_sprite) in goal_query {
+        if !unlock.is_met(&tally, &switch_query) {
+            continue;
+        }
+
+        goal_sprite.color = Color::default();
+        commands
+            .entity(goal_entity)
+            .remove::<DisabledGoal>()
+            .insert(RotateComponent)
+            .observe(goal_observer);
+
+        // Goals that are open as soon as the level starts don't need pointing out.
+        if !goal.is_added() {
+            goal_opened_writer.write(GoalOpened { goal: goal_entity });
+        }
+    }
+}
+
+/// Pans the camera over to a goal that just opened, and points the way there.
+fn announce_opened_goals(
+    mut goal_opened_reader: EventReader<GoalOpened>,
+    mut commands: Commands,
+    goal_assets: Res<GoalAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for event in goal_opened_reader.read() {
+        commands.spawn(play_timeline(goal_assets.opened_timeline.clone()));
+        commands.spawn((
+            Name::new("GoalIndicator"),
+            GoalIndicator { goal: event.goal },
+            Mesh2d(meshes.add(Triangle2d::new(
+                vec2(8.0, 0.0),
+                vec2(-6.0, 5.0),
+                vec2(-6.0, -5.0),
+            ))),
+            MeshMaterial2d(materials.add(Color::WHITE)),
+            DespawnOnExitState::<Screen>::default(),
+        ));
+    }
+}
+
+/// An arrow next to the player that points towards an open goal.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+struct GoalIndicator {
+    goal: Entity,
+}
+
+fn update_goal_indicator(
+    mut commands: Commands,
+    mut indicator_query: Query<(Entity, &GoalIndicator, &mut Transform, &mut Visibility)>,
+    goal_query: Query<&GlobalTransform, With<Goal>>,
+    player: Single<&GlobalTransform, With<Player>>,
+) {
+    let player_pos = player.translation().xy();
+    for (entity, indicator, mut transform, mut visibility) in &mut indicator_query {
+        // The goal is gone once the level changes.
+        let Ok(goal_transform) = goal_query.get(indicator.goal) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let to_goal = goal_transform.translation().xy() - player_pos;
+        *visibility = if to_goal.length() < INDICATOR_HIDE_DISTANCE {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        let direction = to_goal.normalize_or(Vec2::X);
+        transform.translation = (player_pos + direction * INDICATOR_DISTANCE).extend(INDICATOR_Z);
+        transform.rotation = Quat::from_rotation_z(direction.to_angle());
     }
 }
 
@@ -76,6 +240,8 @@ fn goal_observer(
     trigger: Trigger<OnCollisionStart>,
     player_query: Query<(Entity, &mut Transform), With<Player>>,
     level_selection: ResMut<LevelSelection>,
+    ldtk_project: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
     mut event_writer: EventWriter<StartEndSequenceEvent>,
 ) {
     let entity = trigger.collider;
@@ -85,8 +251,9 @@ fn goal_observer(
             LevelSelection::Indices(indices) => indices,
             _ => panic!("level selection should always be Indices in this game"),
         };
+        let project = r!(ldtk_project_assets.get(*ldtk_project));
 
-        if indices.level < 2 {
+        if indices.level + 1 < project.json_data().levels.len() {
             indices.level += 1;
         } else {
             event_writer.write(StartEndSequenceEvent);
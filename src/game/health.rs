@@ -0,0 +1,219 @@
+use crate::game::death_anim::PauseWhenDyingSystems;
+use crate::game::death_anim::PlayerDeath;
+use crate::game::death_anim::PlayerDying;
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+const STARTING_LIVES: u32 = 3;
+const INVULNERABILITY_DURATION: f32 = 1.5;
+/// How long each half of the on-off flash lasts while invulnerable.
+const FLASH_PERIOD: f32 = 0.1;
+const FLASH_ALPHA: f32 = 0.3;
+const KNOCKBACK_VELOCITY: Vec2 = Vec2::new(250.0, 200.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Difficulty, Lives)>();
+    app.register_type::<Health>();
+    app.register_type::<Invulnerable>();
+
+    app.add_event::<PlayerDamage>();
+
+    app.add_systems(StateFlush, PlayerDying.on_enter(lose_life));
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(
+                (
+                    add_player_health,
+                    handle_player_damage,
+                    update_invulnerability,
+                )
+                    .chain(),
+            )
+            .in_set(PausableSystems)
+            .in_set(PauseWhenDyingSystems),
+    );
+}
+
+#[derive(Resource, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Resource)]
+pub enum Difficulty {
+    /// Any hit kills the player, with unlimited retries.
+    #[default]
+    OneHit,
+    /// Hits take away health, and running out of lives restarts the run.
+    Health,
+}
+
+impl Configure for Difficulty {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::OneHit => "One hit",
+            Difficulty::Health => "Health",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::OneHit => Difficulty::Health,
+            Difficulty::Health => Difficulty::OneHit,
+        }
+    }
+
+    fn max_health(self) -> u32 {
+        match self {
+            Difficulty::OneHit => 1,
+            Difficulty::Health => 3,
+        }
+    }
+
+    pub fn has_lives(self) -> bool {
+        self == Difficulty::Health
+    }
+}
+
+/// The number of attempts left in the current run, when the difficulty has lives.
+#[derive(Resource, Reflect, Copy, Clone, Eq, PartialEq, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct Lives(pub u32);
+
+impl Default for Lives {
+    fn default() -> Self {
+        Self(STARTING_LIVES)
+    }
+}
+
+impl Configure for Lives {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        // Every run starts from the title screen.
+        app.add_systems(StateFlush, Screen::Title.on_enter(reset_lives));
+    }
+}
+
+fn reset_lives(mut lives: ResMut<Lives>) {
+    *lives = default();
+}
+
+fn lose_life(difficulty: Res<Difficulty>, mut lives: ResMut<Lives>) {
+    if difficulty.has_lives() {
+        lives.0 = lives.0.saturating_sub(1);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Ignores damage until the timer finishes.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerable(pub Timer);
+
+/// Sent by hazards when they hit the player.
+#[derive(Event, Reflect, Debug, Clone, PartialEq)]
+pub struct PlayerDamage {
+    pub source: Entity,
+    /// What kind of hazard it was, for telemetry.
+    pub cause: Name,
+    /// Where the hit came from, to knock the player away from.
+    pub position: Vec2,
+}
+
+fn add_player_health(
+    mut commands: Commands,
+    difficulty: Res<Difficulty>,
+    player_query: Query<Entity, Added<Player>>,
+) {
+    for entity in &player_query {
+        commands
+            .entity(entity)
+            .insert(Health::new(difficulty.max_health()));
+    }
+}
+
+fn handle_player_damage(
+    mut commands: Commands,
+    mut damage_event_reader: EventReader<PlayerDamage>,
+    mut death_event_writer: EventWriter<PlayerDeath>,
+    mut player: Single<
+        (
+            Entity,
+            &mut Health,
+            &mut LinearVelocity,
+            &GlobalTransform,
+            Has<Invulnerable>,
+        ),
+        With<Player>,
+    >,
+) {
+    let (entity, health, linear_velocity, transform, is_invulnerable) = &mut *player;
+
+    // Only the first hit counts, since it makes the player invulnerable.
+    let damage = rq!(damage_event_reader.read().last());
+    rq!(!*is_invulnerable && health.current > 0);
+
+    health.current -= 1;
+    if health.current == 0 {
+        death_event_writer.write(PlayerDeath {
+            source: damage.source,
+            cause: damage.cause.clone(),
+            position: damage.position,
+        });
+        return;
+    }
+
+    commands
+        .entity(*entity)
+        .insert(Invulnerable(Timer::from_seconds(
+            INVULNERABILITY_DURATION,
+            TimerMode::Once,
+        )));
+
+    // Knock the player up and away from the hazard.
+    let away = if transform.translation().x < damage.position.x {
+        -1.0
+    } else {
+        1.0
+    };
+    linear_velocity.0 = Vec2::new(away * KNOCKBACK_VELOCITY.x, KNOCKBACK_VELOCITY.y);
+}
+
+fn update_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<(Entity, &mut Invulnerable, &mut Sprite)>,
+) {
+    for (entity, mut invulnerable, mut sprite) in &mut player_query {
+        invulnerable.tick(time.delta());
+
+        if invulnerable.finished() {
+            sprite.color.set_alpha(1.0);
+            commands.entity(entity).remove::<Invulnerable>();
+            continue;
+        }
+
+        let is_faded = (invulnerable.elapsed_secs() / FLASH_PERIOD) as u32 % 2 == 0;
+        sprite
+            .color
+            .set_alpha(if is_faded { FLASH_ALPHA } else { 1.0 });
+    }
+}
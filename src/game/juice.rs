@@ -0,0 +1,116 @@
+//! Procedural squash, stretch and pops that make the world feel springy.
+
+use crate::animation::PostTransformSystems;
+use crate::animation::backup::Backup;
+use crate::animation::squash::Squash;
+use crate::game::chain::ConnectedChain;
+use crate::game::player::PLAYER_HEIGHT;
+use crate::game::player::Player;
+use crate::game::player_state::PlayerState;
+use crate::game::player_state::PlayerStateChanged;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+const PLAYER_SQUASH_STIFFNESS: f32 = 300.0;
+const PLAYER_SQUASH_DAMPING: f32 = 12.0;
+const LANDING_IMPULSE: Vec2 = Vec2::new(3.0, -3.0);
+const CHAIN_ATTACH_IMPULSE: Vec2 = Vec2::new(-2.0, 2.5);
+/// The falling speed at which the player is stretched the most.
+const FULL_STRETCH_SPEED: f32 = 800.0;
+const MAX_FALL_STRETCH: Vec2 = Vec2::new(-0.12, 0.18);
+const POP_DURATION: f32 = 0.25;
+/// The biggest scale reached while popping, before shrinking away.
+const POP_SCALE: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Pop>();
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update((
+                add_player_squash,
+                squash_on_land,
+                wobble_on_chain_attach,
+                stretch_while_falling,
+                update_pop,
+            ))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(PostUpdate, apply_pop.in_set(PostTransformSystems::Blend));
+}
+
+/// Grows then shrinks away to nothing before despawning.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+#[require(Backup<Transform>)]
+pub struct Pop(pub Timer);
+
+impl Default for Pop {
+    fn default() -> Self {
+        Self(Timer::from_seconds(POP_DURATION, TimerMode::Once))
+    }
+}
+
+fn add_player_squash(mut commands: Commands, player_query: Query<Entity, Added<Player>>) {
+    for entity in &player_query {
+        commands.entity(entity).insert(Squash::new(
+            Vec2::new(0.0, -PLAYER_HEIGHT / 2.0),
+            PLAYER_SQUASH_STIFFNESS,
+            PLAYER_SQUASH_DAMPING,
+        ));
+    }
+}
+
+fn squash_on_land(
+    mut state_changed_reader: EventReader<PlayerStateChanged>,
+    mut squash: Single<&mut Squash, With<Player>>,
+) {
+    for _ in state_changed_reader
+        .read()
+        .filter(|x| x.to == PlayerState::Landing)
+    {
+        squash.impulse(LANDING_IMPULSE);
+    }
+}
+
+fn wobble_on_chain_attach(mut squash_query: Query<&mut Squash, Added<ConnectedChain>>) {
+    for mut squash in &mut squash_query {
+        squash.impulse(CHAIN_ATTACH_IMPULSE);
+    }
+}
+
+fn stretch_while_falling(
+    mut player: Single<(&mut Squash, &PlayerState, &LinearVelocity), With<Player>>,
+) {
+    let (squash, state, linear_velocity) = &mut *player;
+    let stretch = if **state == PlayerState::Falling {
+        (-linear_velocity.y / FULL_STRETCH_SPEED).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    squash.target = MAX_FALL_STRETCH * stretch;
+}
+
+fn update_pop(mut commands: Commands, time: Res<Time>, mut pop_query: Query<(Entity, &mut Pop)>) {
+    for (entity, mut pop) in &mut pop_query {
+        pop.tick(time.delta());
+        if pop.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn apply_pop(mut pop_query: Query<(&Pop, &mut Transform)>) {
+    const GROW_FRACTION: f32 = 0.4;
+
+    for (pop, mut transform) in &mut pop_query {
+        let t = pop.fraction();
+        let scale = if t < GROW_FRACTION {
+            1.0_f32.lerp(POP_SCALE, t / GROW_FRACTION)
+        } else {
+            POP_SCALE.lerp(0.0, (t - GROW_FRACTION) / (1.0 - GROW_FRACTION))
+        };
+        transform.scale *= Vec3::new(scale, scale, 1.0);
+    }
+}
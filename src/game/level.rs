@@ -1,5 +1,6 @@
 use bevy_ecs_ldtk::prelude::*;
 
+use crate::game::chain_movement::GameLayer;
 use crate::prelude::*;
 use crate::screen::Screen;
 
@@ -182,6 +183,10 @@ fn spawn_wall_collision(
                             ))
                             .insert(RigidBody::Static)
                             .insert(Friction::new(1.0))
+                            .insert(CollisionLayers::new(
+                                [GameLayer::Default, GameLayer::WallLayer],
+                                LayerMask::ALL,
+                            ))
                             .insert(Transform::from_xyz(
                                 (wall_rect.left + wall_rect.right + 1) as f32 * grid_size as f32
                                     / 2.0,
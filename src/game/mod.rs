@@ -1,35 +1,77 @@
 use crate::prelude::*;
 
 mod animated_sprite;
-mod boxes;
+mod aseprite;
+pub mod boss;
+mod bush;
 mod chain;
 mod chain_movement;
+pub mod collectible;
 pub mod death_anim;
+pub mod dialogue;
 mod end_sequence;
+mod enemy;
+mod fire;
 mod goal;
+pub mod health;
+mod juice;
 mod level;
 mod movement;
-mod player;
-mod player_chain;
+pub mod player;
+pub mod player_chain;
+mod player_eyes;
+mod player_state;
+mod projectile;
+pub mod run_stats;
+mod switch;
+pub mod telemetry;
+mod timeline;
+mod trajectory_preview;
 mod tree;
+pub mod tuning;
 mod vines;
 mod world_text;
 
 pub(super) fn plugin(app: &mut App) {
+    // Bevy only accepts so many plugins in one tuple, so they are split into groups.
     app.add_plugins((
-        player::plugin,
-        level::plugin,
-        movement::plugin,
-        animated_sprite::plugin,
-        chain::plugin,
-        chain_movement::plugin,
-        goal::plugin,
-        vines::plugin,
-        player_chain::plugin,
-        death_anim::plugin,
-        boxes::plugin,
-        world_text::plugin,
-        tree::plugin,
-        end_sequence::plugin,
+        (
+            // These register asset loaders, so they go before anything that loads sprites.
+            animated_sprite::plugin,
+            aseprite::plugin,
+            timeline::plugin,
+            player::plugin,
+            level::plugin,
+            movement::plugin,
+            chain::plugin,
+            chain_movement::plugin,
+            goal::plugin,
+            switch::plugin,
+            health::plugin,
+            juice::plugin,
+        ),
+        (
+            vines::plugin,
+            bush::plugin,
+            player_chain::plugin,
+            trajectory_preview::plugin,
+            player_eyes::plugin,
+            player_state::plugin,
+            telemetry::plugin,
+            run_stats::plugin,
+            death_anim::plugin,
+            collectible::plugin,
+            world_text::plugin,
+            dialogue::plugin,
+        ),
+        (
+            projectile::plugin,
+            enemy::plugin,
+            tree::plugin,
+            boss::plugin,
+            end_sequence::plugin,
+            fire::plugin,
+            tuning::plugin,
+        ),
     ));
 }
@@ -7,26 +7,52 @@ use bevy::prelude::*;
 use pyri_state::pattern::StatePattern;
 
 use super::player::ChangePlayerDirection;
-use super::player::ChangePlayerState;
 use crate::game::chain::ConnectedChain;
+use crate::game::chain_movement::GameLayer;
 use crate::game::death_anim::PauseWhenDyingSystems;
+use crate::game::death_anim::PlayerDying;
 use crate::game::player::Player;
-use crate::game::player::PlayerState;
+use crate::game::player::PlayerAction;
+use crate::game::timeline::PlayerInputLocked;
 use crate::prelude::*;
 use crate::screen::Screen;
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Grounded>();
+
     app.add_event::<MovementAction>();
+    app.add_event::<JumpEvent>();
+    app.init_resource::<MovementInput>();
+
+    // Record input right before the fixed steps that consume it.
     app.add_systems(
-        Update,
+        RunFixedMainLoop,
         Screen::Gameplay
-            .on_update((
-                handle_keyboard_input,
-                update_grounded,
-                control_movement,
-                apply_movement_damping,
-                update_idle,
-            ))
+            .on_update(handle_input)
+            .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop)
+            .run_if(
+                Pause::is_disabled
+                    .and(PlayerDying::is_disabled)
+                    .and(PlayerInputLocked::is_disabled),
+            ),
+    );
+    app.add_systems(StateFlush, PlayerInputLocked.on_enter(reset_movement_input));
+    app.add_systems(
+        FixedUpdate,
+        Screen::Gameplay
+            .on_update(
+                (
+                    send_movement_actions,
+                    update_grounded,
+                    update_touching_wall,
+                    control_movement,
+                    apply_movement_damping,
+                    apply_slope_support,
+                    apply_wall_slide,
+                )
+                    .chain(),
+            )
+            .in_set(MovementSystems)
             .in_set(PausableSystems)
             .in_set(PauseWhenDyingSystems),
     );
@@ -35,14 +61,114 @@ pub(super) fn plugin(app: &mut App) {
     // app.insert_gizmo_config(PhysicsGizmos::default(), GizmoConfig::default());
 }
 
+/// The fixed-step systems that move character controllers.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MovementSystems;
+
 /// A marker component indicating that an entity is using a character controller.
 #[derive(Component)]
 pub struct CharacterController;
 
-/// A marker component indicating that an entity is on the ground.
+/// A component indicating that an entity is on the ground.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Grounded {
+    /// The entity being stood on.
+    pub entity: Entity,
+    /// The surface normal of the ground.
+    pub normal: Vector,
+    /// The velocity of the ground at the contact point.
+    pub velocity: Vector,
+}
+
+impl Grounded {
+    /// The direction along the ground that points to the right.
+    fn tangent(&self) -> Vector {
+        Vector::new(self.normal.y, -self.normal.x)
+    }
+}
+
+/// Casts a slightly smaller copy of the character's elliptical collider downwards
+/// to find the ground.
 #[derive(Component)]
+pub struct GroundSensor {
+    shape: Collider,
+    max_distance: Scalar,
+    half_size: Vector,
+}
+
+impl GroundSensor {
+    /// Creates a ground sensor for a character with the given half extents.
+    fn new(half_size: Vector) -> Self {
+        const SENSOR_SCALE: Scalar = 0.95;
+        const DETECTION_MARGIN: Scalar = 4.0;
+
+        let sensor_half_size = half_size * SENSOR_SCALE;
+        Self {
+            shape: Collider::ellipse(sensor_half_size.x, sensor_half_size.y),
+            max_distance: half_size.y - sensor_half_size.y + DETECTION_MARGIN,
+            half_size,
+        }
+    }
+}
+
+/// A component indicating that an entity is touching a wall on the given side.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 #[component(storage = "SparseSet")]
-pub struct Grounded;
+pub struct TouchingWall(pub WallSide);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallSide {
+    Left,
+    Right,
+}
+
+impl WallSide {
+    const ALL: [Self; 2] = [Self::Left, Self::Right];
+
+    fn sign(self) -> Scalar {
+        match self {
+            WallSide::Left => -1.0,
+            WallSide::Right => 1.0,
+        }
+    }
+
+    fn direction(self) -> Dir2 {
+        match self {
+            WallSide::Left => Dir2::NEG_X,
+            WallSide::Right => Dir2::X,
+        }
+    }
+
+    fn turn_away(self) -> ChangePlayerDirection {
+        match self {
+            WallSide::Left => ChangePlayerDirection::TurnRight,
+            WallSide::Right => ChangePlayerDirection::TurnLeft,
+        }
+    }
+}
+
+/// Casts a thin shape to either side of the character controller to detect walls
+/// from the `walls` intgrid.
+#[derive(Component)]
+pub struct WallCaster {
+    shape: Collider,
+    max_distance: Scalar,
+}
+
+impl WallCaster {
+    /// Creates a wall caster for a character with the given half extents.
+    fn new(half_size: Vector) -> Self {
+        const CASTER_WIDTH: Scalar = 2.0;
+        const DETECTION_MARGIN: Scalar = 4.0;
+
+        Self {
+            shape: Collider::rectangle(CASTER_WIDTH, half_size.y),
+            max_distance: half_size.x - CASTER_WIDTH / 2.0 + DETECTION_MARGIN,
+        }
+    }
+}
 /// The acceleration used for character movement.
 #[derive(Component)]
 pub struct MovementAcceleration(Scalar);
@@ -59,6 +185,18 @@ pub struct MovementDampingFactor(Scalar);
 #[derive(Component)]
 pub struct JumpImpulse(Scalar);
 
+/// The maximum falling speed while sliding down a wall.
+#[derive(Component)]
+pub struct MaxWallSlideSpeed(Scalar);
+
+/// The strength of a wall jump.
+#[derive(Component)]
+pub struct WallJumpImpulse(Scalar);
+
+/// The angle from vertical, away from the wall, that a wall jump kicks off at.
+#[derive(Component)]
+pub struct WallJumpAngle(Scalar);
+
 /// The maximum angle a slope can have for a character controller
 /// to be able to climb and jump. If the slope is steeper than this angle,
 /// the character will slide down.
@@ -72,7 +210,8 @@ pub struct CharacterControllerBundle {
     character_controller: CharacterController,
     body: RigidBody,
     collider: Collider,
-    ground_caster: ShapeCaster,
+    ground_sensor: GroundSensor,
+    wall_caster: WallCaster,
     locked_axes: LockedAxes,
     movement: MovementBundle,
 }
@@ -85,6 +224,9 @@ pub struct MovementBundle {
     jump_impulse: JumpImpulse,
     max_slope_angle: MaxSlopeAngle,
     max_player_velocity: MaxPlayerVelocity,
+    max_wall_slide_speed: MaxWallSlideSpeed,
+    wall_jump_impulse: WallJumpImpulse,
+    wall_jump_angle: WallJumpAngle,
 }
 
 impl MovementBundle {
@@ -94,6 +236,9 @@ impl MovementBundle {
         jump_impulse: Scalar,
         max_slope_angle: Scalar,
         max_player_velocity: Scalar,
+        max_wall_slide_speed: Scalar,
+        wall_jump_impulse: Scalar,
+        wall_jump_angle: Scalar,
     ) -> Self {
         Self {
             acceleration: MovementAcceleration(acceleration),
@@ -101,31 +246,24 @@ impl MovementBundle {
             jump_impulse: JumpImpulse(jump_impulse),
             max_slope_angle: MaxSlopeAngle(max_slope_angle),
             max_player_velocity: MaxPlayerVelocity(max_player_velocity),
+            max_wall_slide_speed: MaxWallSlideSpeed(max_wall_slide_speed),
+            wall_jump_impulse: WallJumpImpulse(wall_jump_impulse),
+            wall_jump_angle: WallJumpAngle(wall_jump_angle),
         }
     }
 }
 
-impl Default for MovementBundle {
-    fn default() -> Self {
-        Self::new(2000.0, 0.95, 300.0, avian2d::math::PI * 0.45, 300.0)
-    }
-}
-
 impl CharacterControllerBundle {
-    pub fn new(collider: Collider) -> Self {
-        // Create shape caster as a slightly smaller version of collider
-        let mut caster_shape = collider.clone();
-        // this really should be 0.99 but then it's way too large, idk why
-        caster_shape.set_scale(Vector::ONE * 0.12, 10);
-
+    /// Creates a character controller with an elliptical collider of the given half extents.
+    pub fn new(half_size: Vector, movement: MovementBundle) -> Self {
         Self {
             character_controller: CharacterController,
             body: RigidBody::Dynamic,
-            collider,
-            ground_caster: ShapeCaster::new(caster_shape, Vector::ZERO, 0.0, Dir2::NEG_Y)
-                .with_max_distance(5.0),
+            collider: Collider::ellipse(half_size.x, half_size.y),
+            ground_sensor: GroundSensor::new(half_size),
+            wall_caster: WallCaster::new(half_size),
             locked_axes: LockedAxes::ROTATION_LOCKED,
-            movement: MovementBundle::default(),
+            movement,
         }
     }
 }
@@ -136,12 +274,26 @@ pub enum MovementAction {
     Jump,
 }
 
-fn handle_keyboard_input(
-    mut movement_event_writer: EventWriter<MovementAction>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+/// Sent when a character controller actually jumps.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpEvent {
+    FromGround,
+    OffWall,
+}
+
+/// Movement input recorded every frame, to be consumed by the next fixed step.
+#[derive(Resource, Debug, Default)]
+pub(super) struct MovementInput {
+    pub(super) horizontal: Scalar,
+    jump: bool,
+}
+
+fn handle_input(
+    mut movement_input: ResMut<MovementInput>,
+    action_state: Res<ActionState<PlayerAction>>,
 ) {
-    let left = keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]);
-    let right = keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]);
+    let left = action_state.pressed(&PlayerAction::Left);
+    let right = action_state.pressed(&PlayerAction::Right);
 
     let horizontal = if right && !left {
         1
@@ -151,11 +303,29 @@ fn handle_keyboard_input(
         0
     };
 
-    if horizontal != 0 {
-        movement_event_writer.write(MovementAction::Move(horizontal as Scalar));
+    movement_input.horizontal = horizontal as Scalar;
+
+    // Hold on to the jump until a fixed step has seen it.
+    if action_state.just_pressed(&PlayerAction::Jump) {
+        movement_input.jump = true;
     }
+}
 
-    if keyboard_input.any_just_pressed([KeyCode::Space, KeyCode::ArrowUp, KeyCode::KeyW]) {
+/// Lets go of any held keys, so the player doesn't keep running while input is locked.
+fn reset_movement_input(mut movement_input: ResMut<MovementInput>) {
+    *movement_input = default();
+}
+
+fn send_movement_actions(
+    mut movement_input: ResMut<MovementInput>,
+    mut movement_event_writer: EventWriter<MovementAction>,
+) {
+    if movement_input.horizontal != 0.0 {
+        movement_event_writer.write(MovementAction::Move(movement_input.horizontal));
+    }
+
+    if movement_input.jump {
+        movement_input.jump = false;
         movement_event_writer.write(MovementAction::Jump);
     }
 }
@@ -166,13 +336,16 @@ fn control_movement(
     mut controller_query: Query<(
         &MovementAcceleration,
         &JumpImpulse,
+        &WallJumpImpulse,
+        &WallJumpAngle,
         &mut LinearVelocity,
         &MaxPlayerVelocity,
-        Has<Grounded>,
+        Option<&TouchingWall>,
+        Option<&Grounded>,
         Has<ConnectedChain>,
     )>,
     mut player_direction_writer: EventWriter<ChangePlayerDirection>,
-    mut player_state_writer: EventWriter<ChangePlayerState>,
+    mut jump_event_writer: EventWriter<JumpEvent>,
 ) {
     let delta_time = time.delta_secs();
 
@@ -180,25 +353,32 @@ fn control_movement(
         for (
             movement_acceleration,
             jump_impulse,
+            wall_jump_impulse,
+            wall_jump_angle,
             mut linear_velocity,
             max_velocity,
-            is_grounded,
+            touching_wall,
+            grounded,
             is_on_chain,
         ) in &mut controller_query
         {
-            let jump_damper = if is_grounded { 1.0 } else { 0.6 };
+            let jump_damper = if grounded.is_some() { 1.0 } else { 0.6 };
             let damper = if is_on_chain { 0.6 } else { 1.0 } * jump_damper;
 
             match event {
                 MovementAction::Move(direction) => {
-                    if *direction > 0.0 && linear_velocity.x < **max_velocity
-                        || *direction < 0.0 && linear_velocity.x > -**max_velocity
+                    // Speed is measured relative to the ground, and applied along it so that
+                    // slopes are walked up rather than pushed into.
+                    let ground_velocity = grounded.map_or(Vector::ZERO, |x| x.velocity);
+                    let tangent = grounded.map_or(Vector::X, Grounded::tangent);
+                    let relative_x = linear_velocity.x - ground_velocity.x;
+                    if *direction > 0.0 && relative_x < **max_velocity
+                        || *direction < 0.0 && relative_x > -**max_velocity
                     {
-                        linear_velocity.x +=
-                            damper * *direction * movement_acceleration.0 * delta_time;
+                        linear_velocity.0 +=
+                            tangent * damper * *direction * movement_acceleration.0 * delta_time;
                     }
 
-                    player_state_writer.write(ChangePlayerState::Running);
                     let new_direction = if *direction < 0.0 {
                         ChangePlayerDirection::TurnLeft
                     } else {
@@ -207,9 +387,20 @@ fn control_movement(
                     player_direction_writer.write(new_direction);
                 },
                 MovementAction::Jump => {
-                    if is_grounded {
-                        linear_velocity.y = jump_impulse.0 * damper;
-                        player_state_writer.write(ChangePlayerState::Jumping);
+                    if let Some(grounded) = grounded {
+                        // Carry the ground's upwards momentum into the jump.
+                        linear_velocity.y = jump_impulse.0 * damper + grounded.velocity.y.max(0.0);
+                        jump_event_writer.write(JumpEvent::FromGround);
+                    } else if let Some(&TouchingWall(side)) = touching_wall
+                        && !is_on_chain
+                    {
+                        // Kick off away from the wall.
+                        let away = -side.sign();
+                        let (sin, cos) = wall_jump_angle.0.sin_cos();
+                        linear_velocity.0 = Vector::new(away * sin, cos) * wall_jump_impulse.0;
+
+                        jump_event_writer.write(JumpEvent::OffWall);
+                        player_direction_writer.write(side.turn_away());
                     }
                 },
             }
@@ -220,57 +411,185 @@ fn control_movement(
 /// Updates the [`Grounded`] status for character controllers.
 fn update_grounded(
     mut commands: Commands,
-    mut query: Query<
+    spatial_query: SpatialQuery,
+    query: Query<
         (
             Entity,
-            &ShapeHits,
+            &GroundSensor,
+            &Position,
             &Rotation,
-            &PlayerState,
+            &LinearVelocity,
             Option<&MaxSlopeAngle>,
+            Option<&CollisionLayers>,
         ),
         (With<CharacterController>, With<Player>),
     >,
-    mut player_state_writer: EventWriter<ChangePlayerState>,
+    sensor_query: Query<(), With<Sensor>>,
+    body_query: Query<(&Position, &LinearVelocity, Option<&AngularVelocity>)>,
 ) {
-    for (entity, hits, rotation, player_state, max_slope_angle) in &mut query {
-        // The character is grounded if the shape caster has a hit with a normal
-        // that isn't too steep.
-        let is_grounded = hits.iter().any(|hit| {
-            if let Some(angle) = max_slope_angle {
-                (rotation * -hit.normal2).angle_to(Vector::Y).abs() <= angle.0
-            } else {
-                true
-            }
-        });
+    // Hits past the edge of a ledge still count as ground if they're this close to
+    // the middle of the character, relative to its half width.
+    const LEDGE_TOLERANCE: Scalar = 0.5;
+    // Moving away from the ground faster than this means the character has left it.
+    const MAX_SEPARATION_SPEED: Scalar = 50.0;
+    const MAX_HITS: u32 = 4;
 
-        if is_grounded {
-            commands.entity(entity).insert(Grounded);
-            if *player_state == PlayerState::Jumping {
-                player_state_writer.write(ChangePlayerState::Idle);
-            }
+    for (entity, sensor, position, rotation, linear_velocity, max_slope_angle, collision_layers) in
+        &query
+    {
+        // Only consider what the character can actually collide with, e.g. not the chain
+        // it's attached to.
+        let mask = collision_layers.map_or(LayerMask::ALL, |x| x.filters);
+        let filter = SpatialQueryFilter::from_mask(mask).with_excluded_entities([entity]);
+        let config = ShapeCastConfig::from_max_distance(sensor.max_distance);
+        let hits = spatial_query.shape_hits(
+            &sensor.shape,
+            position.0,
+            rotation.as_radians(),
+            Dir2::NEG_Y,
+            MAX_HITS,
+            &config,
+            &filter,
+        );
+
+        let ground = hits
+            .iter()
+            .filter(|hit| !sensor_query.contains(hit.entity))
+            .filter_map(|hit| {
+                let normal = hit.normal1;
+                let is_walkable =
+                    max_slope_angle.is_none_or(|angle| normal.angle_to(Vector::Y).abs() <= angle.0);
+                if is_walkable {
+                    return Some((hit, normal));
+                }
+
+                // Catching the corner of a ledge gives a steep normal, so treat it as flat
+                // ground while the corner is under the character.
+                let offset = hit.point1 - position.0;
+                (offset.y < 0.0 && offset.x.abs() <= sensor.half_size.x * LEDGE_TOLERANCE)
+                    .then_some((hit, Vector::Y))
+            })
+            .map(|(hit, normal)| {
+                // The velocity of the ground at the contact point, including its rotation.
+                let velocity = body_query.get(hit.entity).map_or(
+                    Vector::ZERO,
+                    |(body_position, body_velocity, angular_velocity)| {
+                        let offset = hit.point1 - body_position.0;
+                        body_velocity.0 + angular_velocity.map_or(0.0, |x| x.0) * offset.perp()
+                    },
+                );
+
+                Grounded {
+                    entity: hit.entity,
+                    normal,
+                    velocity,
+                }
+            })
+            .filter(|ground| {
+                (linear_velocity.0 - ground.velocity).dot(ground.normal) <= MAX_SEPARATION_SPEED
+            })
+            .max_by(|a, b| a.normal.y.total_cmp(&b.normal.y));
+
+        if let Some(ground) = ground {
+            commands.entity(entity).insert(ground);
         } else {
             commands.entity(entity).remove::<Grounded>();
         }
     }
 }
 
-fn update_idle(
-    player_query: Query<(&LinearVelocity, &PlayerState), With<Player>>,
-    mut event_writer: EventWriter<ChangePlayerState>,
+/// Updates the [`TouchingWall`] status for character controllers.
+fn update_touching_wall(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    query: Query<(Entity, &WallCaster, &Position, &Rotation), With<CharacterController>>,
+) {
+    // Only walls that are close to vertical can be slid down and jumped off.
+    const MAX_WALL_NORMAL_Y: Scalar = 0.3;
+
+    for (entity, wall_caster, position, rotation) in &query {
+        let filter =
+            SpatialQueryFilter::from_mask(GameLayer::WallLayer).with_excluded_entities([entity]);
+        let config = ShapeCastConfig::from_max_distance(wall_caster.max_distance);
+
+        let side = WallSide::ALL.into_iter().find(|side| {
+            spatial_query
+                .cast_shape(
+                    &wall_caster.shape,
+                    position.0,
+                    rotation.as_radians(),
+                    side.direction(),
+                    &config,
+                    &filter,
+                )
+                .is_some_and(|hit| {
+                    hit.normal1.x * side.sign() < 0.0 && hit.normal1.y.abs() <= MAX_WALL_NORMAL_Y
+                })
+        });
+
+        if let Some(side) = side {
+            commands.entity(entity).insert(TouchingWall(side));
+        } else {
+            commands.entity(entity).remove::<TouchingWall>();
+        }
+    }
+}
+
+/// Caps the falling speed of character controllers sliding down a wall.
+fn apply_wall_slide(
+    mut query: Query<
+        (&MaxWallSlideSpeed, &mut LinearVelocity, &TouchingWall),
+        (With<Player>, Without<Grounded>, Without<ConnectedChain>),
+    >,
+    mut player_direction_writer: EventWriter<ChangePlayerDirection>,
 ) {
-    for (velocity, player_state) in player_query {
-        if *player_state == PlayerState::Running && velocity.x.abs() < 0.3 {
-            event_writer.write(ChangePlayerState::Idle);
+    for (max_slide_speed, mut linear_velocity, &TouchingWall(side)) in &mut query {
+        if linear_velocity.y >= 0.0 {
+            continue;
         }
+
+        linear_velocity.y = linear_velocity.y.max(-max_slide_speed.0);
+
+        // Face away from the wall while sliding.
+        player_direction_writer.write(side.turn_away());
     }
 }
 
-/// Slows down movement in the X direction.
+/// Slows down movement in the X direction, relative to the ground.
 fn apply_movement_damping(
-    mut query: Query<(&MovementDampingFactor, &mut LinearVelocity), Without<ConnectedChain>>,
+    mut query: Query<
+        (
+            &MovementDampingFactor,
+            &mut LinearVelocity,
+            Option<&Grounded>,
+        ),
+        Without<ConnectedChain>,
+    >,
 ) {
-    for (damping_factor, mut linear_velocity) in &mut query {
+    for (damping_factor, mut linear_velocity, grounded) in &mut query {
+        // Let characters ride along on moving chains and crates.
+        let ground_velocity_x = grounded.map_or(0.0, |x| x.velocity.x);
+
         // We could use `LinearDamping`, but we don't want to dampen movement along the Y axis
-        linear_velocity.x *= damping_factor.0;
+        linear_velocity.x =
+            ground_velocity_x + (linear_velocity.x - ground_velocity_x) * damping_factor.0;
+    }
+}
+
+/// Cancels out gravity along walkable slopes so that characters don't slide down them.
+fn apply_slope_support(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    mut query: Query<
+        (&Grounded, &mut LinearVelocity, Option<&GravityScale>),
+        (With<CharacterController>, Without<ConnectedChain>),
+    >,
+) {
+    let delta_time = time.delta_secs();
+
+    for (grounded, mut linear_velocity, gravity_scale) in &mut query {
+        let gravity = gravity.0 * gravity_scale.map_or(1.0, |x| x.0);
+        let along_slope = gravity - grounded.normal * gravity.dot(grounded.normal);
+        linear_velocity.0 -= along_slope * delta_time;
     }
 }
@@ -1,11 +1,16 @@
 use bevy_ecs_ldtk::prelude::*;
 
-use super::animated_sprite::AnimationIndices;
-use super::animated_sprite::AnimationTimer;
+use super::animated_sprite::AnimationFrameEvent;
+use super::animated_sprite::SpriteAnimation;
+use super::aseprite::AsepriteSheet;
 use super::movement::CharacterControllerBundle;
+use super::player_state::PlayerState;
+use crate::core::audio::AudioSettings;
+use crate::core::audio::sfx_audio;
 use crate::core::camera::SmoothFollow;
 use crate::game::chain::CanAttachChain;
 use crate::game::player_chain::CanShootChain;
+use crate::game::tuning::GameplayTuning;
 use crate::prelude::*;
 use crate::screen::Screen;
 
@@ -13,39 +18,16 @@ use crate::screen::Screen;
 #[reflect(Component)]
 pub(crate) struct Player;
 
-const PLAYER_WIDTH: f32 = 200.0;
-const PLAYER_HEIGHT: f32 = 200.0;
-/// The size of the sprite inside inkscape
-const INKSCAPE_SCALE: f32 = 500.0;
-const PLAYER_SCALE_X: f32 = PLAYER_WIDTH / INKSCAPE_SCALE;
-const PLAYER_SCALE_Y: f32 = PLAYER_HEIGHT / INKSCAPE_SCALE;
-
-// spritesheet indices
-const IDLE_INDEX: usize = 0;
-const RUN_FIRST_INDEX: usize = 1;
-const RUN_LAST_INDEX: usize = 4;
-const JUMP_INDEX: usize = 5;
-
-// info for the eyes
-const EYE_RADIUS: f32 = 25.0 * PLAYER_SCALE_X;
-const LEFT_EYE_POS_X: f32 = convert_pos(263.0, PLAYER_SCALE_X);
-const LEFT_EYE_POS_Y: f32 = convert_pos(157.0, -PLAYER_SCALE_Y);
-const RIGHT_EYE_POS_X: f32 = convert_pos(385.0, PLAYER_SCALE_X);
-const RIGHT_EYE_POS_Y: f32 = convert_pos(155.0, -PLAYER_SCALE_Y);
-
-/// Convert between coords in inkscape to here (0, 0) being in top left vs in centre
-const fn convert_pos(pos: f32, scale: f32) -> f32 {
-    scale * (pos - INKSCAPE_SCALE / 2.0)
-}
+pub(super) const PLAYER_WIDTH: f32 = 200.0;
+pub(super) const PLAYER_HEIGHT: f32 = 200.0;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Player>();
     app.register_type::<Direction>();
 
     app.add_event::<ChangePlayerDirection>();
-    app.add_event::<ChangePlayerState>();
 
-    app.configure::<PlayerAssets>();
+    app.configure::<(PlayerAssets, PlayerAction)>();
 
     app.register_ldtk_entity::<PlayerBundle>("player");
 
@@ -57,8 +39,7 @@ pub(super) fn plugin(app: &mut App) {
                 process_player,
                 set_camera_follow,
                 change_player_direction,
-                change_player_state,
-                handle_eye_bobble,
+                play_footsteps,
             ))
             .in_set(PausableSystems),
     );
@@ -67,8 +48,10 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(AssetCollection, Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct PlayerAssets {
-    #[asset(path = "image/player_spritesheet.png")]
-    player_spritesheet: Handle<Image>,
+    #[asset(path = "image/player_spritesheet.aseprite.json")]
+    player_spritesheet: Handle<AsepriteSheet>,
+    #[asset(path = "audio/sfx/253168__suntemple__sfx-ui-button-click.ogg")]
+    footstep_sound: Handle<AudioSource>,
 }
 
 impl Configure for PlayerAssets {
@@ -78,13 +61,47 @@ impl Configure for PlayerAssets {
     }
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
-#[reflect(Component)]
-pub enum PlayerState {
-    #[default]
-    Idle,
-    Running,
-    Jumping,
+/// What the player can do, bound to keys and gamepad buttons by the [`InputMap`] resource.
+#[derive(Actionlike, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PlayerAction {
+    Left,
+    Right,
+    Jump,
+    Grapple,
+    Interact,
+}
+
+impl Configure for PlayerAction {
+    fn configure(app: &mut App) {
+        app.init_resource::<ActionState<Self>>();
+        app.insert_resource(
+            InputMap::default()
+                .with(Self::Left, KeyCode::KeyA)
+                .with(Self::Left, KeyCode::ArrowLeft)
+                .with(Self::Left, GamepadButton::DPadLeft)
+                .with(Self::Right, KeyCode::KeyD)
+                .with(Self::Right, KeyCode::ArrowRight)
+                .with(Self::Right, GamepadButton::DPadRight)
+                .with(Self::Jump, KeyCode::Space)
+                .with(Self::Jump, KeyCode::KeyW)
+                .with(Self::Jump, KeyCode::ArrowUp)
+                .with(Self::Jump, GamepadButton::South)
+                .with(Self::Grapple, MouseButton::Left)
+                .with(Self::Interact, KeyCode::KeyE)
+                .with(Self::Interact, GamepadButton::West),
+        );
+        app.add_plugins(InputManagerPlugin::<Self>::default());
+    }
+}
+
+impl PlayerAction {
+    pub const ALL: [Self; 5] = [
+        Self::Left,
+        Self::Right,
+        Self::Jump,
+        Self::Grapple,
+        Self::Interact,
+    ];
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
@@ -110,203 +127,80 @@ impl ChangePlayerDirection {
     }
 }
 
-#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ChangePlayerState {
-    Running,
-    Idle,
-    Jumping,
-}
-
-impl ChangePlayerState {
-    fn as_state(&self) -> PlayerState {
-        match self {
-            ChangePlayerState::Running => PlayerState::Running,
-            ChangePlayerState::Idle => PlayerState::Idle,
-            ChangePlayerState::Jumping => PlayerState::Jumping,
-        }
-    }
-
-    fn as_indices(&self) -> AnimationIndices {
-        match self {
-            ChangePlayerState::Running => AnimationIndices::new(RUN_FIRST_INDEX, RUN_LAST_INDEX),
-            ChangePlayerState::Idle => AnimationIndices::single(IDLE_INDEX),
-            ChangePlayerState::Jumping => AnimationIndices::single(JUMP_INDEX),
-        }
-    }
-}
-
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
-#[reflect(Component)]
-pub enum PlayerEye {
-    Left,
-    Right,
-}
-
-impl PlayerEye {
-    fn get_pos(&self) -> Vec2 {
-        match self {
-            PlayerEye::Left => Vec2::new(LEFT_EYE_POS_X, LEFT_EYE_POS_Y),
-            PlayerEye::Right => Vec2::new(RIGHT_EYE_POS_X, RIGHT_EYE_POS_Y),
-        }
-    }
-
-    fn get_pos_with_dir(&self, direction: Direction) -> Vec2 {
-        let pos = self.get_pos();
-        if direction == Direction::Left {
-            Vec2::new(-pos.x, pos.y)
-        } else {
-            Vec2::new(pos.x, pos.y)
-        }
-    }
-}
-
 #[derive(Bundle, Default, LdtkEntity)]
 struct PlayerBundle {
     player: Player,
 }
 
-#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Deref, DerefMut, Reflect)]
-#[reflect(Component)]
-struct PlayerEyeBobble(pub Vec2);
-
 fn process_player(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
     assets: Res<PlayerAssets>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    sheets: Res<Assets<AsepriteSheet>>,
     new_player: Query<Entity, Added<Player>>,
     current_level: Res<LevelSelection>,
+    tuning: ConfigRef<GameplayTuning>,
 ) {
+    let tuning = r!(tuning.get());
+    let sheet = r!(sheets.get(&assets.player_spritesheet));
     for player_entity in new_player.iter() {
-        let layout = TextureAtlasLayout::from_grid(UVec2::splat(500), 6, 1, None, None);
-        let texture_atlas_layout = texture_atlas_layouts.add(layout);
-
-        let mut player_sprite = Sprite::from_atlas_image(
-            assets.player_spritesheet.clone(),
-            TextureAtlas {
-                layout: texture_atlas_layout,
-                index: 0,
-            },
-        );
+        let mut player_sprite = sheet.sprite();
         player_sprite.custom_size = Some((PLAYER_WIDTH, PLAYER_HEIGHT).into());
 
-        let eye_mesh = meshes.add(Circle::new(EYE_RADIUS));
-        let eye_material = materials.add(Color::BLACK);
-
         commands
             .entity(player_entity)
             .insert((
                 player_sprite,
                 PlayerState::default(),
-                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-                AnimationIndices::single(IDLE_INDEX),
+                SpriteAnimation::new(sheet.clips.clone(), PlayerState::default().animation_clip()),
                 Direction::default(),
-                CharacterControllerBundle::new(Collider::ellipse(
-                    PLAYER_WIDTH / 2.0,
-                    PLAYER_HEIGHT / 2.0,
-                )),
-                Friction::new(0.1).with_combine_rule(CoefficientCombine::Min),
-                Restitution::new(0.3).with_combine_rule(CoefficientCombine::Min),
-                ColliderDensity(4.0),
-                GravityScale(2.0),
+                CharacterControllerBundle::new(
+                    Vec2::new(PLAYER_WIDTH / 2.0, PLAYER_HEIGHT / 2.0),
+                    tuning.movement.bundle(),
+                ),
+                tuning.player.physics_bundle(),
+                tuning.player.gravity_scale(false),
                 CollisionEventsEnabled,
                 CanAttachChain,
             ))
             .insert_if(CanShootChain, || match *current_level {
                 LevelSelection::Indices(level_indices) => level_indices.level > 0,
                 _ => false,
-            })
-            .with_children(|player| {
-                let eyes = [PlayerEye::Left, PlayerEye::Right];
-                for eye in eyes {
-                    let position = eye.get_pos().extend(1.0);
-
-                    player.spawn((
-                        Mesh2d(eye_mesh.clone()),
-                        MeshMaterial2d(eye_material.clone()),
-                        PlayerEyeBobble::default(),
-                        eye,
-                        Transform::from_translation(position),
-                    ));
-                }
             });
     }
 }
 
+/// Points the camera at the player when they spawn, leaving timelines free to move it elsewhere.
 fn set_camera_follow(
     mut camera: Single<&mut SmoothFollow, With<Camera2d>>,
-    player_entity: Single<Entity, With<Player>>,
+    player_entity: Single<Entity, Added<Player>>,
 ) {
     camera.target = *player_entity;
 }
 
+/// Plays a footstep on the frames of the player's animations that ask for one.
+fn play_footsteps(
+    mut commands: Commands,
+    mut frame_event_reader: EventReader<AnimationFrameEvent>,
+    player_query: Query<(), With<Player>>,
+    assets: Res<PlayerAssets>,
+    audio_settings: Res<AudioSettings>,
+) {
+    for event in frame_event_reader.read() {
+        if event.event == "footstep" && player_query.contains(event.entity) {
+            commands.spawn(sfx_audio(&audio_settings, assets.footstep_sound.clone()));
+        }
+    }
+}
+
 fn change_player_direction(
-    mut player: Single<(&mut Direction, &mut Sprite, &Children), With<Player>>,
-    mut player_eyes_query: Query<(&mut Transform, &PlayerEye)>,
+    mut player: Single<(&mut Direction, &mut Sprite), With<Player>>,
     mut direction_event_reader: EventReader<ChangePlayerDirection>,
 ) {
     for event in direction_event_reader.read() {
-        let existing_direction = *player.0;
         *player.0 = event.as_direction();
         player.1.flip_x = match event {
             ChangePlayerDirection::TurnLeft => true,
             ChangePlayerDirection::TurnRight => false,
         };
-
-        for eye_entity in player.2.iter() {
-            let player_eye = player_eyes_query.get_mut(eye_entity);
-            if let Ok((mut player_eye_transform, player_eye)) = player_eye {
-                let old_pos = player_eye.get_pos_with_dir(existing_direction);
-                let existing_offset = player_eye_transform.translation.xy() - old_pos;
-                let position = player_eye.get_pos_with_dir(event.as_direction()) + existing_offset;
-                *player_eye_transform = Transform::from_translation(position.extend(1.0));
-            }
-        }
-    }
-}
-
-fn handle_eye_bobble(
-    time: Res<Time>,
-    player_eye_bobble: Query<(&mut PlayerEyeBobble, &mut Transform, &PlayerEye)>,
-    player_direction: Single<&Direction, With<Player>>,
-) {
-    const EYE_MOVEMENT_SPEED: f32 = 10.0;
-    for (mut bobble, mut transform, eye_type) in player_eye_bobble {
-        let eye_position = eye_type.get_pos_with_dir(**player_direction);
-        let position_diff = eye_position - transform.translation.xy();
-        let offset_diff = position_diff - **bobble;
-        let normalised_offset = offset_diff.try_normalize();
-        if let Some(normalised_offset) = normalised_offset {
-            let offset_move = normalised_offset * time.delta_secs() * EYE_MOVEMENT_SPEED;
-            if offset_move.length() >= offset_diff.length() {
-                transform.translation += offset_diff.extend(0.0);
-                **bobble = generate_random_eye_offset();
-            } else {
-                transform.translation += offset_move.extend(0.0);
-            }
-        } else {
-            **bobble = generate_random_eye_offset();
-        }
-    }
-}
-
-fn generate_random_eye_offset() -> Vec2 {
-    const EYE_X_OFFSET: f32 = 8.0;
-    const EYE_Y_OFFSET: f32 = 16.0;
-
-    let x = (random::<f32>() - 0.5) * EYE_X_OFFSET;
-    let y = (random::<f32>() - 0.5) * EYE_Y_OFFSET;
-
-    Vec2::new(x, y)
-}
-
-fn change_player_state(
-    mut player: Single<(&mut PlayerState, &mut AnimationIndices), With<Player>>,
-    mut state_event_reader: EventReader<ChangePlayerState>,
-) {
-    for event in state_event_reader.read() {
-        *player.0 = event.as_state();
-        *player.1 = event.as_indices();
     }
 }
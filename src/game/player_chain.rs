@@ -8,6 +8,8 @@ use crate::game::chain::PivotChainPart;
 use crate::game::chain::convert_chain_to_parts;
 use crate::game::death_anim::PauseWhenDyingSystems;
 use crate::game::player::Player;
+use crate::game::player::PlayerAction;
+use crate::game::timeline::PlayerInputLocked;
 use crate::prelude::*;
 use crate::screen::Screen;
 
@@ -22,13 +24,22 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
         Screen::Gameplay
-            .on_update((
-                handle_input,
-                read_shoot_chain_event,
-                update_shooting_chain,
-                convert_chain,
-                handle_despawn_timer,
-            ))
+            .on_update(handle_input.run_if(PlayerInputLocked::is_disabled))
+            .in_set(PausableSystems)
+            .in_set(PauseWhenDyingSystems),
+    );
+    app.add_systems(
+        FixedUpdate,
+        Screen::Gameplay
+            .on_update(
+                (
+                    read_shoot_chain_event,
+                    update_shooting_chain,
+                    convert_chain,
+                    handle_despawn_timer,
+                )
+                    .chain(),
+            )
             .in_set(PausableSystems)
             .in_set(PauseWhenDyingSystems),
     );
@@ -44,6 +55,13 @@ pub struct ShootingChain {
     end_position: Vec2,
 }
 
+impl ShootingChain {
+    /// How far the chain has reached towards its end position, from 0 to 1.
+    pub fn progress(&self, length: ChainLength, origin: Vec2) -> f32 {
+        (PLAYER_CHAIN_SIZE * *length / origin.distance(self.end_position)).clamp(0.0, 1.0)
+    }
+}
+
 #[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 pub struct GeneratedChain;
@@ -74,6 +92,7 @@ pub struct ShootingChainBundle {
     attached_entity: AttachedEntity,
     current_length: ChainLength,
     sprite: Sprite,
+    interpolation: TransformInterpolation,
 }
 
 #[derive(Event, Debug, Clone, Copy, PartialEq, Deref, DerefMut, Default)]
@@ -139,7 +158,7 @@ fn update_shooting_chain(
         &mut ChainLength,
         &AttachedEntity,
     )>,
-    attached_entity_query: Query<(Entity, &GlobalTransform)>,
+    attached_entity_query: Query<(Entity, &Position, &GlobalTransform)>,
     mut chain_hit_event_writer: EventWriter<ChainHitEnd>,
     spatial_query: SpatialQuery,
 ) {
@@ -153,14 +172,14 @@ fn update_shooting_chain(
     {
         **chain_length += CHAIN_SPEED * time.delta_secs();
 
-        if let Ok((attached_entity, attached_transform)) =
+        if let Ok((attached_entity, attached_position, attached_transform)) =
             attached_entity_query.get(**attached_entity)
         {
-            let origin_position = attached_transform.translation().xy();
+            let origin_position = attached_position.0;
             let to_vector = (**shooting_chain - origin_position).normalize();
             let rotation_to_end_pos = Quat::from_rotation_arc(Vec3::Y, to_vector.extend(0.0));
 
-            let chain_pos = attached_transform.translation()
+            let chain_pos = origin_position.extend(attached_transform.translation().z)
                 + 0.5 * (rotation_to_end_pos * Vec3::Y) * **chain_length * PLAYER_CHAIN_SIZE;
 
             *chain_transform = Transform {
@@ -199,7 +218,7 @@ fn read_shoot_chain_event(
     mut event_reader: EventReader<ShootChain>,
     mut commands: Commands,
     existing_chains: Query<Entity, With<ShootingChain>>,
-    player: Single<(Entity, &GlobalTransform), With<Player>>,
+    player: Single<(Entity, &Position), With<Player>>,
     chain_assets: Res<ChainAssets>,
     spatial_query: SpatialQuery,
 ) {
@@ -209,7 +228,7 @@ fn read_shoot_chain_event(
             commands.entity(existing_chain).despawn();
         }
 
-        let origin_point = player.1.translation().xy();
+        let origin_point = player.1.0;
         let to_vector = (**event - origin_point).normalize();
         let direction = Dir2::new(to_vector).unwrap();
 
@@ -237,13 +256,14 @@ fn read_shoot_chain_event(
                 custom_size: Some(Vec2::splat(PLAYER_CHAIN_SIZE)),
                 ..default()
             },
+            interpolation: TransformInterpolation,
         });
     }
 }
 
 fn handle_input(
     player_chain: Single<Has<CanShootChain>, With<Player>>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
+    action_state: Res<ActionState<PlayerAction>>,
     window: Single<&Window, With<PrimaryWindow>>,
     camera: Single<(&Camera, &GlobalTransform), (With<Camera2d>, With<IsDefaultUiCamera>)>,
     mut shoot_chain_event_writer: EventWriter<ShootChain>,
@@ -252,7 +272,7 @@ fn handle_input(
         return;
     }
 
-    if mouse_input.just_pressed(MouseButton::Left)
+    if action_state.just_pressed(&PlayerAction::Grapple)
         && let Some(mouse_position) = window.cursor_position()
         && let Ok(world_pos) = camera.0.viewport_to_world_2d(camera.1, mouse_position)
     {
@@ -0,0 +1,389 @@
+use avian2d::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use super::player::Direction;
+use super::player::PLAYER_HEIGHT;
+use super::player::PLAYER_WIDTH;
+use super::player::Player;
+use super::player_chain::ShootingChain;
+use super::player_state::PlayerState;
+use crate::core::camera::CameraRoot;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// The size of the sprite inside inkscape
+const INKSCAPE_SCALE: f32 = 500.0;
+const PLAYER_SCALE_X: f32 = PLAYER_WIDTH / INKSCAPE_SCALE;
+const PLAYER_SCALE_Y: f32 = PLAYER_HEIGHT / INKSCAPE_SCALE;
+
+// info for the eyes
+const LEFT_EYE_POS_X: f32 = convert_pos(263.0, PLAYER_SCALE_X);
+const LEFT_EYE_POS_Y: f32 = convert_pos(157.0, -PLAYER_SCALE_Y);
+const RIGHT_EYE_POS_X: f32 = convert_pos(385.0, PLAYER_SCALE_X);
+const RIGHT_EYE_POS_Y: f32 = convert_pos(155.0, -PLAYER_SCALE_Y);
+
+/// Convert between coords in inkscape to here (0, 0) being in top left vs in centre
+const fn convert_pos(pos: f32, scale: f32) -> f32 {
+    scale * (pos - INKSCAPE_SCALE / 2.0)
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<ConfigHandle<EyeConfig>>();
+    app.register_type::<PlayerEye>();
+    app.register_type::<EyeExpression>();
+    app.register_type::<EyeBlink>();
+    app.register_type::<EyeLook>();
+    app.register_type::<ShownExpression>();
+
+    app.add_systems(
+        Update,
+        state!(Screen::Title | Screen::Loading)
+            .on_update(EyeConfig::progress.track_progress::<BevyState<Screen>>()),
+    );
+    // Runs while dying too, so that the eyes can show the death expression.
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(
+                (
+                    spawn_player_eyes,
+                    update_eye_look,
+                    update_eye_blink,
+                    update_eye_expression,
+                    show_eye_expression,
+                )
+                    .chain(),
+            )
+            .in_set(PausableSystems),
+    );
+}
+
+/// How the player's eyes move and look, hot-reloaded from `config/eyes.ron`.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EyeConfig {
+    /// How far the eyes move from their resting position towards what they're looking at.
+    look_distance: f32,
+    /// How quickly the eyes move towards what they're looking at.
+    look_speed: f32,
+    /// The range of time between blinks, in seconds.
+    blink_interval: (f32, f32),
+    blink_duration: f32,
+    /// The downwards speed above which the player looks surprised while falling.
+    surprised_fall_speed: f32,
+    /// The speed above which the player squints while swinging.
+    squint_swing_speed: f32,
+    expressions: EyeExpressions,
+}
+
+impl Config for EyeConfig {
+    const FILE: &'static str = "eyes.ron";
+
+    fn on_load(&self, world: &mut World) {
+        let material = r!(world.get_resource_mut::<Assets<ColorMaterial>>()).add(Color::BLACK);
+        let mut meshes = r!(world.get_resource_mut::<Assets<Mesh>>());
+        let shapes = EyeExpression::ALL
+            .into_iter()
+            .map(|expression| {
+                let shapes = self
+                    .expressions
+                    .get(expression)
+                    .iter()
+                    .map(|shape| (meshes.add(shape.shape.mesh()), shape.transform()))
+                    .collect();
+                (expression, shapes)
+            })
+            .collect();
+        world.insert_resource(EyeMeshes { material, shapes });
+
+        // Redraw any eyes that are already spawned so changes show up immediately.
+        let eyes = world
+            .query_filtered::<Entity, With<ShownExpression>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        for entity in eyes {
+            world.entity_mut(entity).remove::<ShownExpression>();
+        }
+    }
+}
+
+/// The shapes that make up each eye for every expression.
+#[derive(Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EyeExpressions {
+    normal: Vec<EyeShape>,
+    blink: Vec<EyeShape>,
+    surprised: Vec<EyeShape>,
+    squint: Vec<EyeShape>,
+    dead: Vec<EyeShape>,
+}
+
+impl EyeExpressions {
+    fn get(&self, expression: EyeExpression) -> &[EyeShape] {
+        match expression {
+            EyeExpression::Normal => &self.normal,
+            EyeExpression::Blink => &self.blink,
+            EyeExpression::Surprised => &self.surprised,
+            EyeExpression::Squint => &self.squint,
+            EyeExpression::Dead => &self.dead,
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EyeShape {
+    shape: EyePrimitive,
+    /// Relative to the centre of the eye, with positive x pointing the way the player faces.
+    #[serde(default)]
+    offset: (f32, f32),
+    #[serde(default)]
+    angle_degrees: f32,
+}
+
+impl EyeShape {
+    fn transform(&self) -> Transform {
+        Transform::from_xyz(self.offset.0, self.offset.1, 0.0)
+            .with_rotation(Quat::from_rotation_z(self.angle_degrees.to_radians()))
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize)]
+enum EyePrimitive {
+    Circle { radius: f32 },
+    Ellipse { half_size: (f32, f32) },
+    Rect { size: (f32, f32) },
+}
+
+impl EyePrimitive {
+    fn mesh(&self) -> Mesh {
+        match *self {
+            EyePrimitive::Circle { radius } => Circle::new(radius).into(),
+            EyePrimitive::Ellipse { half_size } => Ellipse::new(half_size.0, half_size.1).into(),
+            EyePrimitive::Rect { size } => Rectangle::new(size.0, size.1).into(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct EyeMeshes {
+    material: Handle<ColorMaterial>,
+    shapes: HashMap<EyeExpression, Vec<(Handle<Mesh>, Transform)>>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum PlayerEye {
+    Left,
+    Right,
+}
+
+impl PlayerEye {
+    fn get_pos(&self) -> Vec2 {
+        match self {
+            PlayerEye::Left => Vec2::new(LEFT_EYE_POS_X, LEFT_EYE_POS_Y),
+            PlayerEye::Right => Vec2::new(RIGHT_EYE_POS_X, RIGHT_EYE_POS_Y),
+        }
+    }
+
+    fn get_pos_with_dir(&self, direction: Direction) -> Vec2 {
+        let pos = self.get_pos();
+        if direction == Direction::Left {
+            Vec2::new(-pos.x, pos.y)
+        } else {
+            Vec2::new(pos.x, pos.y)
+        }
+    }
+}
+
+/// The expression the player's eyes should be showing.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[reflect(Component)]
+pub enum EyeExpression {
+    #[default]
+    Normal,
+    Blink,
+    Surprised,
+    Squint,
+    Dead,
+}
+
+impl EyeExpression {
+    const ALL: [Self; 5] = [
+        EyeExpression::Normal,
+        EyeExpression::Blink,
+        EyeExpression::Surprised,
+        EyeExpression::Squint,
+        EyeExpression::Dead,
+    ];
+}
+
+/// Counts down to the player's next blink, or to the end of the current one.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct EyeBlink {
+    timer: Timer,
+    closed: bool,
+}
+
+/// How far an eye has moved from its resting position.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+struct EyeLook(Vec2);
+
+/// The expression an eye's shapes were last spawned for.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ShownExpression(EyeExpression);
+
+fn spawn_player_eyes(mut commands: Commands, new_player: Query<Entity, Added<Player>>) {
+    for player_entity in &new_player {
+        commands
+            .entity(player_entity)
+            .insert((
+                EyeExpression::default(),
+                // Opens straight away, picking a random time until the first blink.
+                EyeBlink {
+                    timer: Timer::default(),
+                    closed: true,
+                },
+            ))
+            .with_children(|player| {
+                for eye in [PlayerEye::Left, PlayerEye::Right] {
+                    player.spawn((
+                        eye,
+                        EyeLook::default(),
+                        Transform::from_translation(eye.get_pos().extend(1.0)),
+                        Visibility::default(),
+                    ));
+                }
+            });
+    }
+}
+
+fn update_eye_look(
+    time: Res<Time>,
+    config: ConfigRef<EyeConfig>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera_root: Res<CameraRoot>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    shooting_chain_query: Query<&ShootingChain>,
+    player: Single<(&Direction, &PlayerState, &GlobalTransform, &Children), With<Player>>,
+    mut eye_query: Query<(&PlayerEye, &mut EyeLook, &mut Transform)>,
+) {
+    let config = r!(config.get());
+    let (direction, state, player_transform, children) = *player;
+    let (camera, camera_transform) = r!(camera_query.get(camera_root.primary));
+
+    // Prefer the grapple target, then the cursor.
+    let target = shooting_chain_query
+        .iter()
+        .next()
+        .map(|chain| **chain)
+        .or_else(|| {
+            let cursor = window.cursor_position()?;
+            camera.viewport_to_world_2d(camera_transform, cursor).ok()
+        })
+        .filter(|_| *state != PlayerState::Dying)
+        .map(|target| {
+            player_transform
+                .affine()
+                .inverse()
+                .transform_point3(target.extend(0.0))
+                .xy()
+        });
+
+    for &child in children {
+        let (eye, mut look, mut transform) = cq!(eye_query.get_mut(child));
+        let position = eye.get_pos_with_dir(*direction);
+
+        let target_look = match target {
+            Some(target) => (target - position).normalize_or_zero() * config.look_distance,
+            None if *state == PlayerState::Dying => Vec2::ZERO,
+            None => match direction {
+                Direction::Left => Vec2::NEG_X * config.look_distance,
+                Direction::Right => Vec2::X * config.look_distance,
+            },
+        };
+        look.smooth_nudge(&target_look, config.look_speed, time.delta_secs());
+
+        transform.translation = (position + look.0).extend(1.0);
+        // Mirror the expression shapes to match the way the player faces.
+        transform.scale.x = match direction {
+            Direction::Left => -1.0,
+            Direction::Right => 1.0,
+        };
+    }
+}
+
+fn update_eye_blink(
+    time: Res<Time>,
+    config: ConfigRef<EyeConfig>,
+    mut blink_query: Query<&mut EyeBlink>,
+) {
+    let config = r!(config.get());
+    for mut blink in &mut blink_query {
+        blink.timer.tick(time.delta());
+        cq!(blink.timer.finished());
+
+        blink.closed = !blink.closed;
+        let duration = if blink.closed {
+            config.blink_duration
+        } else {
+            let (min, max) = config.blink_interval;
+            thread_rng().gen_range(min..=max.max(min))
+        };
+        blink.timer = Timer::from_seconds(duration, TimerMode::Once);
+    }
+}
+
+fn update_eye_expression(
+    config: ConfigRef<EyeConfig>,
+    mut player_query: Query<(&mut EyeExpression, &EyeBlink, &PlayerState, &LinearVelocity)>,
+) {
+    let config = r!(config.get());
+    for (mut expression, blink, state, linear_velocity) in &mut player_query {
+        let next = match state {
+            PlayerState::Dying => EyeExpression::Dead,
+            PlayerState::Falling if linear_velocity.y < -config.surprised_fall_speed => {
+                EyeExpression::Surprised
+            },
+            PlayerState::Swinging if linear_velocity.length() > config.squint_swing_speed => {
+                EyeExpression::Squint
+            },
+            _ if blink.closed => EyeExpression::Blink,
+            _ => EyeExpression::Normal,
+        };
+        expression.set_if_neq(next);
+    }
+}
+
+fn show_eye_expression(
+    mut commands: Commands,
+    eye_meshes: Option<Res<EyeMeshes>>,
+    player_query: Query<(&EyeExpression, &Children), With<Player>>,
+    eye_query: Query<Option<&ShownExpression>, With<PlayerEye>>,
+) {
+    let eye_meshes = rq!(eye_meshes);
+    for (&expression, children) in &player_query {
+        for &child in children {
+            let shown = cq!(eye_query.get(child));
+            cq!(shown.is_none_or(|x| x.0 != expression));
+
+            let shapes = c!(eye_meshes.shapes.get(&expression));
+            commands
+                .entity(child)
+                .despawn_related::<Children>()
+                .insert(ShownExpression(expression))
+                .with_children(|eye| {
+                    for (mesh, transform) in shapes {
+                        eye.spawn((
+                            Mesh2d(mesh.clone()),
+                            MeshMaterial2d(eye_meshes.material.clone()),
+                            *transform,
+                        ));
+                    }
+                });
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use avian2d::prelude::*;
+
+use super::animated_sprite::SpriteAnimation;
+use super::chain::ConnectedChain;
+use super::death_anim::PlayerDying;
+use super::movement::Grounded;
+use super::movement::JumpEvent;
+use super::movement::MovementInput;
+use super::movement::MovementSystems;
+use super::movement::TouchingWall;
+use super::player::Player;
+use super::player_chain::ShootingChain;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// How long the player stays in [`PlayerState::Landing`] before moving on.
+const LANDING_DURATION: f32 = 0.1;
+/// The horizontal speed, relative to the ground, below which the player stops running.
+const MIN_RUN_SPEED: f32 = 0.3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PlayerState>();
+    app.register_type::<TimeInPlayerState>();
+
+    app.add_event::<PlayerStateChanged>();
+
+    // Runs while dying too, so that the player can enter `Dying`.
+    app.add_systems(
+        FixedUpdate,
+        Screen::Gameplay
+            .on_update(update_player_state)
+            .after(MovementSystems)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(update_player_animation)
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+#[require(TimeInPlayerState)]
+pub enum PlayerState {
+    #[default]
+    Idle,
+    Running,
+    Jumping,
+    Falling,
+    /// Briefly after touching down from the air.
+    Landing,
+    WallSliding,
+    WallJumping,
+    /// Attached to a chain.
+    Swinging,
+    /// While a shot chain is still flying.
+    Grappling,
+    Dying,
+}
+
+impl PlayerState {
+    /// The name of the animation tag in the player's spritesheet.
+    pub fn animation_clip(self) -> &'static str {
+        match self {
+            PlayerState::Idle => "idle",
+            PlayerState::Running => "run",
+            PlayerState::Jumping => "jump",
+            PlayerState::Falling => "fall",
+            PlayerState::Landing => "land",
+            PlayerState::WallSliding => "wall_slide",
+            PlayerState::WallJumping => "wall_jump",
+            PlayerState::Swinging => "swing",
+            PlayerState::Grappling => "grapple",
+            PlayerState::Dying => "dying",
+        }
+    }
+
+    /// Picks the next state from the current one. Earlier checks take priority.
+    fn next(self, context: &PlayerStateContext) -> Self {
+        if context.is_dying {
+            return PlayerState::Dying;
+        }
+        if context.is_on_chain {
+            return PlayerState::Swinging;
+        }
+        if context.is_grappling {
+            return PlayerState::Grappling;
+        }
+        match context.jump {
+            Some(JumpEvent::FromGround) => return PlayerState::Jumping,
+            Some(JumpEvent::OffWall) => return PlayerState::WallJumping,
+            None => {},
+        }
+
+        if context.is_grounded {
+            let is_moving = context.relative_velocity.x.abs() >= MIN_RUN_SPEED;
+            return match self {
+                PlayerState::Jumping
+                | PlayerState::Falling
+                | PlayerState::WallSliding
+                | PlayerState::WallJumping => PlayerState::Landing,
+                PlayerState::Landing if context.time_in_state < LANDING_DURATION => {
+                    PlayerState::Landing
+                },
+                // Keep running while sliding to a stop.
+                PlayerState::Running if is_moving => PlayerState::Running,
+                _ if context.wants_to_move => PlayerState::Running,
+                _ => PlayerState::Idle,
+            };
+        }
+
+        if context.is_wall_sliding {
+            return PlayerState::WallSliding;
+        }
+        match self {
+            PlayerState::Jumping | PlayerState::WallJumping
+                if context.relative_velocity.y > 0.0 =>
+            {
+                self
+            },
+            _ => PlayerState::Falling,
+        }
+    }
+}
+
+/// How long the player has been in its current [`PlayerState`], in seconds.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct TimeInPlayerState(pub f32);
+
+/// Sent whenever the player's [`PlayerState`] changes.
+#[derive(Event, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerStateChanged {
+    pub from: PlayerState,
+    pub to: PlayerState,
+}
+
+/// Everything the state machine looks at to decide on a transition.
+#[derive(Debug, Clone, Copy)]
+struct PlayerStateContext {
+    is_dying: bool,
+    is_on_chain: bool,
+    is_grappling: bool,
+    jump: Option<JumpEvent>,
+    is_grounded: bool,
+    is_wall_sliding: bool,
+    wants_to_move: bool,
+    /// Velocity relative to the ground, or absolute while in the air.
+    relative_velocity: Vec2,
+    time_in_state: f32,
+}
+
+fn update_player_state(
+    time: Res<Time>,
+    player_dying: CurrentRef<PlayerDying>,
+    movement_input: Res<MovementInput>,
+    mut jump_event_reader: EventReader<JumpEvent>,
+    mut state_changed_writer: EventWriter<PlayerStateChanged>,
+    shooting_chain_query: Query<(), With<ShootingChain>>,
+    mut player: Single<
+        (
+            &mut PlayerState,
+            &mut TimeInPlayerState,
+            &LinearVelocity,
+            Option<&Grounded>,
+            Has<TouchingWall>,
+            Has<ConnectedChain>,
+        ),
+        With<Player>,
+    >,
+) {
+    let (state, time_in_state, linear_velocity, grounded, is_touching_wall, is_on_chain) =
+        &mut *player;
+
+    let is_grounded = grounded.is_some();
+    let context = PlayerStateContext {
+        is_dying: player_dying.is_enabled(),
+        is_on_chain: *is_on_chain,
+        is_grappling: !shooting_chain_query.is_empty(),
+        jump: jump_event_reader.read().last().copied(),
+        is_grounded,
+        is_wall_sliding: *is_touching_wall
+            && !is_grounded
+            && !*is_on_chain
+            && linear_velocity.y < 0.0,
+        wants_to_move: movement_input.horizontal != 0.0,
+        relative_velocity: linear_velocity.0 - grounded.map_or(Vec2::ZERO, |x| x.velocity),
+        time_in_state: time_in_state.0,
+    };
+
+    let next = state.next(&context);
+    if next == **state {
+        time_in_state.0 += time.delta_secs();
+        return;
+    }
+
+    state_changed_writer.write(PlayerStateChanged {
+        from: **state,
+        to: next,
+    });
+    **state = next;
+    time_in_state.0 = 0.0;
+}
+
+fn update_player_animation(
+    mut player_query: Query<(&PlayerState, &mut SpriteAnimation), Changed<PlayerState>>,
+) {
+    for (state, mut animation) in &mut player_query {
+        animation.play(state.animation_clip());
+    }
+}
@@ -0,0 +1,231 @@
+//! Things thrown or shot at a target, recycled from a pool once they're spent.
+
+use crate::game::health::Health;
+use crate::game::health::Invulnerable;
+use crate::game::health::PlayerDamage;
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// How bouncy projectiles are, for the ones with bounces to spare.
+const PROJECTILE_RESTITUTION: f32 = 0.6;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Projectile>();
+
+    app.configure::<ProjectilePool>();
+
+    app.add_event::<ShootProjectile>();
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(
+                (
+                    shoot_projectiles,
+                    tick_projectile_lifetimes,
+                    recycle_spent_projectiles,
+                )
+                    .chain(),
+            )
+            .in_set(PausableSystems),
+    );
+}
+
+/// Hurts whatever it hits, then passes through, bounces off, or is recycled.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct Projectile {
+    /// Whoever shot it, which it never hurts.
+    pub owner: Entity,
+    /// The projectile is recycled when this finishes.
+    pub lifetime: Timer,
+    /// How many more times it can hit something without health before it's recycled.
+    pub bounces: u32,
+    /// How much health it takes from anything but the player, who always takes a single hit.
+    pub damage: u32,
+    /// How many more targets it can pass through before it's recycled.
+    ///
+    /// Piercing projectiles are sensors, so they pass through obstacles instead of bouncing.
+    pub pierce: u32,
+    /// Which layers it's on and which it hits, ignoring the rest.
+    pub layers: CollisionLayers,
+    spent: bool,
+}
+
+impl Projectile {
+    pub fn new(owner: Entity, lifetime: f32) -> Self {
+        Self {
+            owner,
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+            bounces: 0,
+            damage: 1,
+            pierce: 0,
+            layers: default(),
+            spent: false,
+        }
+    }
+
+    pub fn with_bounces(mut self, bounces: u32) -> Self {
+        self.bounces = bounces;
+        self
+    }
+
+    pub fn with_damage(mut self, damage: u32) -> Self {
+        self.damage = damage;
+        self
+    }
+
+    pub fn with_pierce(mut self, pierce: u32) -> Self {
+        self.pierce = pierce;
+        self
+    }
+
+    pub fn with_layers(mut self, layers: CollisionLayers) -> Self {
+        self.layers = layers;
+        self
+    }
+}
+
+/// Spent projectiles, disabled until they're shot again.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct ProjectilePool(Vec<Entity>);
+
+impl Configure for ProjectilePool {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Screen::Gameplay.on_exit(clear_projectile_pool));
+    }
+}
+
+impl ProjectilePool {
+    /// Takes a spent projectile that hasn't been despawned since.
+    fn take(
+        &mut self,
+        pooled_query: &Query<(), (With<Projectile>, With<Disabled>)>,
+    ) -> Option<Entity> {
+        std::iter::from_fn(|| self.0.pop()).find(|&entity| pooled_query.contains(entity))
+    }
+}
+
+// Disabled entities are skipped by `DespawnOnExitState`, so they have to be despawned here.
+fn clear_projectile_pool(mut commands: Commands, mut pool: ResMut<ProjectilePool>) {
+    for entity in pool.0.drain(..) {
+        commands.entity(entity).try_despawn();
+    }
+}
+
+/// Shoots a projectile, reusing a spent one from the [`ProjectilePool`] if there is one.
+#[derive(Event, Clone, Debug)]
+pub struct ShootProjectile {
+    pub name: Name,
+    pub projectile: Projectile,
+    pub sprite: Sprite,
+    pub collider: Collider,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+}
+
+fn shoot_projectiles(
+    mut commands: Commands,
+    mut shoot_event_reader: EventReader<ShootProjectile>,
+    mut pool: ResMut<ProjectilePool>,
+    pooled_query: Query<(), (With<Projectile>, With<Disabled>)>,
+) {
+    for event in shoot_event_reader.read() {
+        let event = event.clone();
+        let entity = pool.take(&pooled_query).unwrap_or_else(|| {
+            commands
+                .spawn(DespawnOnExitState::<Screen>::default())
+                .observe(on_projectile_hit)
+                .id()
+        });
+        let is_piercing = event.projectile.pierce > 0;
+
+        commands
+            .entity(entity)
+            .remove::<(Disabled, Sensor)>()
+            .insert((
+                event.name,
+                event.sprite,
+                Transform::from_translation(event.position.extend(0.0)),
+                LinearVelocity(event.velocity),
+                AngularVelocity(event.angular_velocity),
+                event.collider,
+                event.projectile.layers,
+                RigidBody::Dynamic,
+                Restitution::new(PROJECTILE_RESTITUTION),
+                CollisionEventsEnabled,
+                event.projectile,
+            ))
+            .insert_if(Sensor, || is_piercing);
+    }
+}
+
+fn on_projectile_hit(
+    trigger: Trigger<OnCollisionStart>,
+    mut projectile_query: Query<(&mut Projectile, &Name, &GlobalTransform)>,
+    player_query: Query<(), With<Player>>,
+    mut health_query: Query<&mut Health, (Without<Player>, Without<Invulnerable>)>,
+    mut damage_event_writer: EventWriter<PlayerDamage>,
+) {
+    let projectile_entity = trigger.target();
+    let other_entity = trigger.collider;
+    let (mut projectile, name, transform) = r!(projectile_query.get_mut(projectile_entity));
+    if projectile.spent
+        || other_entity == projectile.owner
+        || trigger.body == Some(projectile.owner)
+    {
+        return;
+    }
+
+    let is_target = if player_query.contains(other_entity) {
+        damage_event_writer.write(PlayerDamage {
+            source: projectile_entity,
+            cause: name.clone(),
+            position: transform.translation().xy(),
+        });
+        true
+    } else if let Ok(mut health) = health_query.get_mut(other_entity) {
+        health.current = health.current.saturating_sub(projectile.damage);
+        true
+    } else {
+        false
+    };
+
+    // Targets use up pierce, and anything else is an obstacle to bounce off.
+    let remaining = if is_target {
+        &mut projectile.pierce
+    } else {
+        &mut projectile.bounces
+    };
+    if *remaining == 0 {
+        projectile.spent = true;
+    } else {
+        *remaining -= 1;
+    }
+}
+
+fn tick_projectile_lifetimes(time: Res<Time>, mut projectile_query: Query<&mut Projectile>) {
+    for mut projectile in &mut projectile_query {
+        if projectile.lifetime.tick(time.delta()).finished() {
+            projectile.spent = true;
+        }
+    }
+}
+
+fn recycle_spent_projectiles(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_query: Query<(Entity, &Projectile)>,
+) {
+    for (entity, projectile) in &projectile_query {
+        if projectile.spent {
+            commands.entity(entity).insert(Disabled);
+            pool.0.push(entity);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Statistics for the current run, shown on the results screen and compared against personal bests.
+
+use bevy_ecs_ldtk::prelude::*;
+use bevy_simple_prefs::Prefs;
+use bevy_simple_prefs::PrefsPlugin;
+
+use crate::game::collectible::CollectibleTally;
+use crate::game::death_anim::PlayerDeath;
+use crate::game::end_sequence::StartEndSequenceEvent;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// Where records are saved: a file on native, or local storage on web.
+const RECORDS_FILE: &str = "records.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(RunStats, PersonalBest)>();
+
+    app.add_plugins(PrefsPlugin::<SavedRecords> {
+        filename: RECORDS_FILE.to_string(),
+        ..default()
+    });
+}
+
+/// How the player did in a single level, across every attempt at it.
+#[derive(Reflect, Clone, Default, Debug)]
+pub struct LevelStats {
+    /// Time spent in the level, in seconds, including attempts that ended in death.
+    pub time: f32,
+    pub deaths: u32,
+    /// Collectibles from the latest attempt.
+    pub collectibles: CollectibleTally,
+}
+
+/// Fed by gameplay systems as the player progresses through a run.
+#[derive(Resource, Reflect, Clone, Default, Debug)]
+#[reflect(Resource)]
+pub struct RunStats {
+    /// Indexed by level.
+    pub levels: Vec<LevelStats>,
+    /// Set when the final goal is reached, after which the stats stop changing.
+    pub finished: bool,
+    /// The personal best from before this run, for comparison.
+    pub previous_best: PersonalBest,
+}
+
+impl Configure for RunStats {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        // Every run starts from the title screen.
+        app.add_systems(StateFlush, Screen::Title.on_enter(reset_run_stats));
+        app.add_systems(
+            Update,
+            Screen::Gameplay
+                .on_update((tick_run_time, count_deaths, finish_run))
+                .in_set(PausableSystems),
+        );
+    }
+}
+
+impl RunStats {
+    /// Returns the stats for a level, adding it if it hasn't been visited yet.
+    pub fn level_mut(&mut self, level_selection: &LevelSelection) -> &mut LevelStats {
+        let index = match level_selection {
+            LevelSelection::Indices(indices) => indices.level,
+            _ => 0,
+        };
+        if self.levels.len() <= index {
+            self.levels.resize_with(index + 1, default);
+        }
+        &mut self.levels[index]
+    }
+
+    pub fn total_time(&self) -> f32 {
+        self.levels.iter().map(|x| x.time).sum()
+    }
+
+    pub fn total_deaths(&self) -> u32 {
+        self.levels.iter().map(|x| x.deaths).sum()
+    }
+
+    pub fn total_points(&self) -> u32 {
+        self.levels.iter().map(|x| x.collectibles.points).sum()
+    }
+
+    /// Whether every level was played, rather than starting from a later one.
+    pub fn played_every_level(&self) -> bool {
+        self.levels.iter().all(|x| x.time > 0.0)
+    }
+}
+
+fn reset_run_stats(mut run_stats: ResMut<RunStats>) {
+    *run_stats = default();
+}
+
+fn tick_run_time(
+    time: Res<Time>,
+    level_selection: Res<LevelSelection>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    if run_stats.finished {
+        return;
+    }
+    run_stats.level_mut(&level_selection).time += time.delta_secs();
+}
+
+fn count_deaths(
+    mut death_event_reader: EventReader<PlayerDeath>,
+    level_selection: Res<LevelSelection>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for _ in death_event_reader.read() {
+        run_stats.level_mut(&level_selection).deaths += 1;
+    }
+}
+
+fn finish_run(
+    mut end_event_reader: EventReader<StartEndSequenceEvent>,
+    mut run_stats: ResMut<RunStats>,
+    mut personal_best: ResMut<PersonalBest>,
+) {
+    if end_event_reader.read().last().is_none() || run_stats.finished {
+        return;
+    }
+
+    run_stats.finished = true;
+    run_stats.previous_best = personal_best.clone();
+    personal_best.update(&run_stats);
+}
+
+/// Records that are kept across sessions.
+#[derive(Prefs, Reflect, Default)]
+struct SavedRecords {
+    personal_best: PersonalBest,
+}
+
+/// The best results from every finished run, each tracked separately.
+#[derive(Resource, Reflect, Clone, PartialEq, Default, Debug)]
+#[reflect(Resource)]
+pub struct PersonalBest {
+    pub total_time: Option<f32>,
+    pub total_deaths: Option<u32>,
+    /// The fastest time for each level, indexed by level.
+    pub level_times: Vec<Option<f32>>,
+}
+
+impl Configure for PersonalBest {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl PersonalBest {
+    fn update(&mut self, run_stats: &RunStats) {
+        // Totals from a run that skipped levels can't be compared.
+        if run_stats.played_every_level() {
+            let total_time = run_stats.total_time();
+            if self.total_time.is_none_or(|x| total_time < x) {
+                self.total_time = Some(total_time);
+            }
+
+            let total_deaths = run_stats.total_deaths();
+            if self.total_deaths.is_none_or(|x| total_deaths < x) {
+                self.total_deaths = Some(total_deaths);
+            }
+        }
+
+        if self.level_times.len() < run_stats.levels.len() {
+            self.level_times.resize(run_stats.levels.len(), None);
+        }
+        for (best, level) in self.level_times.iter_mut().zip(&run_stats.levels) {
+            // Skip levels that weren't played, like when starting from a later level.
+            if level.time > 0.0 && best.is_none_or(|x| level.time < x) {
+                *best = Some(level.time);
+            }
+        }
+    }
+}
+
+/// Formats a duration in seconds as `m:ss.cc`.
+pub fn format_time(secs: f32) -> String {
+    let centis = (secs.max(0.0) * 100.0).round() as u32;
+    format!(
+        "{}:{:02}.{:02}",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100,
+    )
+}
@@ -0,0 +1,65 @@
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// The size of the area the player has to touch to turn a switch on.
+const SWITCH_HITBOX_SIZE: f32 = 24.0;
+const SWITCH_OFF_COLOR: Color = Color::srgb(0.45, 0.4, 0.3);
+const SWITCH_ON_COLOR: Color = Color::srgb(0.91, 0.77, 0.28);
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Switch>();
+
+    app.register_ldtk_entity::<SwitchBundle>("switch");
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(process_switches)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Turns on for the rest of the attempt when the player touches it.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[reflect(Component)]
+pub struct Switch {
+    pub on: bool,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct SwitchBundle {
+    switch: Switch,
+    #[sprite_sheet]
+    sprite: Sprite,
+    sensor: Sensor,
+    collision_events_enabled: CollisionEventsEnabled,
+}
+
+fn process_switches(
+    mut commands: Commands,
+    switch_query: Query<(Entity, &mut Sprite), Added<Switch>>,
+) {
+    for (entity, mut sprite) in switch_query {
+        sprite.color = SWITCH_OFF_COLOR;
+        commands
+            .entity(entity)
+            .insert(Collider::rectangle(SWITCH_HITBOX_SIZE, SWITCH_HITBOX_SIZE))
+            .observe(on_switch_touched);
+    }
+}
+
+fn on_switch_touched(
+    trigger: Trigger<OnCollisionStart>,
+    player_query: Query<(), With<Player>>,
+    mut switch_query: Query<(&mut Switch, &mut Sprite)>,
+) {
+    if !player_query.contains(trigger.collider) {
+        return;
+    }
+    let (mut switch, mut sprite) = r!(switch_query.get_mut(trigger.target()));
+    switch.on = true;
+    sprite.color = SWITCH_ON_COLOR;
+}
@@ -0,0 +1,127 @@
+//! Records every death to a JSON-lines file, so designers can see where players struggle.
+
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::death_anim::PauseWhenDyingSystems;
+use crate::game::death_anim::PlayerDeath;
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+/// The file that deaths are appended to, relative to the project root.
+#[cfg(feature = "native")]
+const DEATH_LOG_FILE: &str = "deaths.jsonl";
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(LevelTime, DeathLog)>();
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(record_death)
+            .in_set(PausableSystems),
+    );
+}
+
+/// How long the player has spent in the current level, in seconds.
+#[derive(Resource, Reflect, Copy, Clone, Default, PartialEq, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct LevelTime(pub f32);
+
+impl Configure for LevelTime {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay
+                .on_update((reset_level_time, tick_level_time).chain())
+                .in_set(PausableSystems)
+                .in_set(PauseWhenDyingSystems),
+        );
+    }
+}
+
+/// Restarts the clock whenever a level is spawned, which includes retrying after dying.
+fn reset_level_time(mut level_time: ResMut<LevelTime>, new_level: Query<(), Added<LevelIid>>) {
+    if !new_level.is_empty() {
+        *level_time = default();
+    }
+}
+
+fn tick_level_time(time: Res<Time>, mut level_time: ResMut<LevelTime>) {
+    level_time.0 += time.delta_secs();
+}
+
+/// A single death, as written to the death log.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct DeathRecord {
+    pub level_iid: String,
+    pub position: [f32; 2],
+    pub cause: String,
+    pub time_in_level: f32,
+}
+
+/// Every death recorded so far, including from earlier sessions on native builds.
+#[derive(Resource, Reflect, Clone, Default, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct DeathLog(pub Vec<DeathRecord>);
+
+impl Configure for DeathLog {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        #[cfg(feature = "native")]
+        app.add_systems(Startup, load_death_log);
+    }
+}
+
+#[cfg(feature = "native")]
+fn death_log_path() -> std::path::PathBuf {
+    bevy::asset::io::file::FileAssetReader::get_base_path().join(DEATH_LOG_FILE)
+}
+
+#[cfg(feature = "native")]
+fn load_death_log(mut death_log: ResMut<DeathLog>) {
+    // The file won't exist until the first death.
+    let Ok(contents) = std::fs::read_to_string(death_log_path()) else {
+        return;
+    };
+    for line in contents.lines().filter(|x| !x.trim().is_empty()) {
+        death_log.push(c!(serde_json::from_str(line)));
+    }
+}
+
+fn record_death(
+    mut death_event_reader: EventReader<PlayerDeath>,
+    mut death_log: ResMut<DeathLog>,
+    level_time: Res<LevelTime>,
+    level_iid: Single<&LevelIid>,
+    player_transform: Single<&GlobalTransform, With<Player>>,
+) {
+    for death in death_event_reader.read() {
+        let record = DeathRecord {
+            level_iid: level_iid.as_str().to_string(),
+            position: player_transform.translation().xy().to_array(),
+            cause: death.cause.to_string(),
+            time_in_level: level_time.0,
+        };
+        let line = r!(serde_json::to_string(&record));
+        death_log.push(record);
+
+        #[cfg(feature = "native")]
+        {
+            use std::io::Write as _;
+
+            let mut file = r!(std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(death_log_path()));
+            r!(writeln!(file, "{line}"));
+        }
+
+        // There's no file system to write to, so log it for collecting instead.
+        #[cfg(not(feature = "native"))]
+        info!("Death: {line}");
+    }
+}
@@ -0,0 +1,488 @@
+//! Scripted sequences of camera moves, dialogue, fades and the like, loaded from `.timeline.ron` files.
+
+use bevy::ecs::system::SystemParam;
+use bevy_common_assets::ron::RonAssetPlugin;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::animation::PostColorSystems;
+use crate::core::audio::AudioSettings;
+use crate::core::audio::sfx_audio;
+use crate::core::camera::CameraRoot;
+use crate::core::camera::SmoothFollow;
+use crate::game::boss::Boss;
+use crate::game::fire::IgniteFire;
+use crate::game::goal::Goal;
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::ShowGameOverMenu;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        PlayerInputLocked,
+        TimelinePlayer,
+        CameraZoom,
+        TimelineFade,
+        TimelineDialogue,
+    )>();
+
+    app.add_plugins(RonAssetPlugin::<Timeline>::new(&["timeline.ron"]));
+
+    app.add_systems(
+        StateFlush,
+        Screen::Gameplay.on_exit((PlayerInputLocked::disable, remove_camera_zoom)),
+    );
+}
+
+/// A list of steps that are played one after another.
+#[derive(Asset, Reflect, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Timeline {
+    pub steps: Vec<TimelineStep>,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone)]
+#[reflect(no_field_bounds)]
+pub enum TimelineStep {
+    /// Does nothing for a number of seconds.
+    Wait(f32),
+    /// Starts every step at once, and finishes when the longest one does.
+    Parallel(Vec<TimelineStep>),
+    /// Makes the camera follow an entity, then waits for it to catch up.
+    PanTo {
+        target: TimelineTarget,
+        duration: f32,
+    },
+    /// Zooms the camera to an orthographic scale, where smaller is closer.
+    Zoom {
+        scale: f32,
+        duration: f32,
+    },
+    /// Spawns a prefab at an entity's position.
+    Spawn {
+        prefab: TimelinePrefab,
+        at: TimelineTarget,
+    },
+    /// Shows a line of dialogue at the bottom of the screen.
+    Dialogue {
+        text: String,
+        duration: f32,
+    },
+    /// Fades a screen overlay to an opacity between 0 and 1.
+    Fade {
+        alpha: f32,
+        duration: f32,
+    },
+    /// Plays a sound, by its path in the assets folder.
+    Sound(String),
+    SetState(TimelineState),
+    /// Stops the player from moving or shooting chains, until an `EnableInput` step
+    /// or the screen exits.
+    DisableInput,
+    EnableInput,
+}
+
+impl TimelineStep {
+    /// How long the step lasts, in seconds.
+    fn duration(&self) -> f32 {
+        match self {
+            Self::Wait(duration)
+            | Self::PanTo { duration, .. }
+            | Self::Zoom { duration, .. }
+            | Self::Dialogue { duration, .. }
+            | Self::Fade { duration, .. } => *duration,
+            Self::Parallel(steps) => steps.iter().map(Self::duration).fold(0.0, f32::max),
+            _ => 0.0,
+        }
+    }
+}
+
+/// An entity in the current level that a step acts on.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TimelineTarget {
+    Player,
+    Goal,
+    Boss,
+    /// An LDtk entity, by its IID.
+    Iid(String),
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelinePrefab {
+    /// Sets the level alight.
+    Fire,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineState {
+    GameOverMenu,
+}
+
+/// Whether player input is ignored, so a timeline can take control.
+#[derive(State, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[state(log_flush)]
+#[reflect(Resource)]
+pub struct PlayerInputLocked;
+
+impl Configure for PlayerInputLocked {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_state::<Self>();
+    }
+}
+
+/// Plays a [`Timeline`], despawning itself when it's done.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct TimelinePlayer {
+    pub timeline: Handle<Timeline>,
+    step: usize,
+    elapsed: f32,
+    started: bool,
+}
+
+impl Configure for TimelinePlayer {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay
+                .on_update(play_timelines)
+                .in_set(PausableSystems),
+        );
+    }
+}
+
+impl TimelinePlayer {
+    pub fn new(timeline: Handle<Timeline>) -> Self {
+        Self {
+            timeline,
+            step: 0,
+            elapsed: 0.0,
+            started: false,
+        }
+    }
+}
+
+/// Plays a timeline for as long as the current [`Screen`] lasts.
+pub fn play_timeline(timeline: Handle<Timeline>) -> impl Bundle {
+    (
+        Name::new("TimelinePlayer"),
+        TimelinePlayer::new(timeline),
+        DespawnOnExitState::<Screen>::default(),
+    )
+}
+
+fn play_timelines(
+    time: Res<Time>,
+    timelines: Res<Assets<Timeline>>,
+    mut timeline_query: Query<(Entity, &mut TimelinePlayer)>,
+    mut actions: TimelineActions,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut player) in &mut timeline_query {
+        let timeline = c!(timelines.get(&player.timeline));
+        // A new timeline starts its first step this frame, so no time has passed in it yet.
+        if player.started {
+            player.elapsed += dt;
+        }
+
+        // Steps that take no time run back to back, so a timeline can do several things at once.
+        while let Some(step) = timeline.steps.get(player.step) {
+            if !player.started {
+                actions.start(step);
+                player.started = true;
+            }
+
+            let duration = step.duration();
+            if player.elapsed < duration {
+                break;
+            }
+            player.elapsed -= duration;
+            player.step += 1;
+            player.started = false;
+        }
+
+        if player.step >= timeline.steps.len() {
+            actions.commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[derive(SystemParam)]
+struct TimelineActions<'w, 's> {
+    commands: Commands<'w, 's>,
+    asset_server: Res<'w, AssetServer>,
+    audio_settings: Res<'w, AudioSettings>,
+    camera_root: Res<'w, CameraRoot>,
+    camera_query: Query<'w, 's, (&'static mut SmoothFollow, &'static Projection)>,
+    target_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            Has<Player>,
+            Has<Goal>,
+            Has<Boss>,
+            Option<&'static EntityIid>,
+        ),
+    >,
+    fade_query: Query<'w, 's, (Entity, &'static TimelineFade)>,
+    dialogue_query: Query<'w, 's, Entity, With<TimelineDialogue>>,
+    ignite_event_writer: EventWriter<'w, IgniteFire>,
+    input_locked: NextMut<'w, 's, PlayerInputLocked>,
+    gameover_menu: NextMut<'w, 's, ShowGameOverMenu>,
+}
+
+impl TimelineActions<'_, '_> {
+    fn target(&self, target: &TimelineTarget) -> Option<(Entity, Vec2)> {
+        self.target_query
+            .iter()
+            .find(|(_, _, is_player, is_goal, is_boss, iid)| match target {
+                TimelineTarget::Player => *is_player,
+                TimelineTarget::Goal => *is_goal,
+                TimelineTarget::Boss => *is_boss,
+                TimelineTarget::Iid(x) => iid.is_some_and(|iid| iid.as_str() == x),
+            })
+            .map(|(entity, gt, ..)| (entity, gt.translation().xy()))
+    }
+
+    fn start(&mut self, step: &TimelineStep) {
+        match step {
+            TimelineStep::Wait(_) => {},
+            TimelineStep::Parallel(steps) => {
+                for step in steps {
+                    self.start(step);
+                }
+            },
+            TimelineStep::PanTo { target, .. } => {
+                let (target, _) = r!(self.target(target));
+                let (mut follow, _) = r!(self.camera_query.get_mut(self.camera_root.primary));
+                follow.target = target;
+            },
+            TimelineStep::Zoom { scale, duration } => {
+                let (_, projection) = r!(self.camera_query.get(self.camera_root.primary));
+                let from = r!(match projection {
+                    Projection::Orthographic(x) => Some(x.scale),
+                    _ => None,
+                });
+                self.commands
+                    .entity(self.camera_root.primary)
+                    .insert(CameraZoom::new(from, *scale, *duration));
+            },
+            TimelineStep::Spawn { prefab, at } => {
+                let (_, position) = r!(self.target(at));
+                match prefab {
+                    TimelinePrefab::Fire => {
+                        self.ignite_event_writer.write(IgniteFire { position });
+                    },
+                }
+            },
+            TimelineStep::Dialogue { text, duration } => {
+                for entity in &self.dialogue_query {
+                    self.commands.entity(entity).despawn();
+                }
+                self.commands.spawn(dialogue_box(text, *duration));
+            },
+            TimelineStep::Fade { alpha, duration } => {
+                if let Ok((entity, fade)) = self.fade_query.single() {
+                    self.commands.entity(entity).insert(TimelineFade::new(
+                        fade.alpha(),
+                        *alpha,
+                        *duration,
+                    ));
+                } else {
+                    self.commands
+                        .spawn(fade_overlay(TimelineFade::new(0.0, *alpha, *duration)));
+                }
+            },
+            TimelineStep::Sound(path) => {
+                self.commands.spawn(sfx_audio(
+                    &self.audio_settings,
+                    self.asset_server.load(path.clone()),
+                ));
+            },
+            TimelineStep::SetState(TimelineState::GameOverMenu) => {
+                self.gameover_menu.enable_default();
+            },
+            TimelineStep::DisableInput => self.input_locked.enable_default(),
+            TimelineStep::EnableInput => self.input_locked.disable(),
+        }
+    }
+}
+
+/// Eases the camera's orthographic scale towards a target.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+struct CameraZoom {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl Configure for CameraZoom {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay
+                .on_update(apply_camera_zoom)
+                .in_set(PausableSystems),
+        );
+    }
+}
+
+impl CameraZoom {
+    fn new(from: f32, to: f32, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+}
+
+fn apply_camera_zoom(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut camera_query: Query<(Entity, &mut CameraZoom, &mut Projection)>,
+) {
+    for (entity, mut zoom, mut projection) in &mut camera_query {
+        let Projection::Orthographic(projection) = &mut *projection else {
+            continue;
+        };
+        zoom.elapsed += time.delta_secs();
+        let t = if zoom.duration > 0.0 {
+            zoom.elapsed / zoom.duration
+        } else {
+            1.0
+        };
+        let t = EaseFunction::SmoothStep.sample_clamped(t);
+        projection.scale = zoom.from.lerp(zoom.to, t);
+
+        if t >= 1.0 {
+            commands.entity(entity).remove::<CameraZoom>();
+        }
+    }
+}
+
+fn remove_camera_zoom(mut commands: Commands, camera_root: Res<CameraRoot>) {
+    commands.entity(camera_root.primary).remove::<CameraZoom>();
+}
+
+/// Fades a full-screen overlay between two opacities.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+struct TimelineFade {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl Configure for TimelineFade {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            PostUpdate,
+            apply_timeline_fade.in_set(PostColorSystems::Blend),
+        );
+    }
+}
+
+impl TimelineFade {
+    fn new(from: f32, to: f32, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    fn alpha(&self) -> f32 {
+        if self.duration > 0.0 {
+            self.from
+                .lerp(self.to, (self.elapsed / self.duration).clamp(0.0, 1.0))
+        } else {
+            self.to
+        }
+    }
+}
+
+fn fade_overlay(fade: TimelineFade) -> impl Bundle {
+    (
+        widget::overlay(-1),
+        fade,
+        ThemeColor::Body.set::<BackgroundColor>(),
+        DespawnOnExitState::<Screen>::default(),
+    )
+}
+
+fn apply_timeline_fade(
+    time: Res<Time>,
+    mut fade_query: Query<(&mut TimelineFade, &mut BackgroundColor)>,
+) {
+    for (mut fade, mut color) in &mut fade_query {
+        fade.elapsed += time.delta_secs();
+        color.0.set_alpha(fade.alpha());
+    }
+}
+
+/// A line of dialogue that despawns when its timer finishes.
+#[derive(Component, Reflect, Debug, Clone, PartialEq, Eq, Deref, DerefMut)]
+#[reflect(Component)]
+struct TimelineDialogue(Timer);
+
+impl Configure for TimelineDialogue {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay
+                .on_update(tick_timeline_dialogue)
+                .in_set(PausableSystems),
+        );
+    }
+}
+
+fn dialogue_box(text: &str, duration: f32) -> impl Bundle {
+    (
+        Name::new("TimelineDialogue"),
+        TimelineDialogue(Timer::from_seconds(duration, TimerMode::Once)),
+        Node {
+            align_items: AlignItems::End,
+            justify_content: JustifyContent::Center,
+            padding: UiRect::bottom(Vw(5.0)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        GlobalZIndex(0),
+        DespawnOnExitState::<Screen>::default(),
+        children![(
+            Name::new("DialogueBox"),
+            Node {
+                max_width: Vw(70.0),
+                padding: UiRect::all(Vw(2.0)),
+                ..default()
+            },
+            ThemeColor::Popup.set::<BackgroundColor>(),
+            children![widget::label(text)],
+        )],
+    )
+}
+
+fn tick_timeline_dialogue(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut dialogue_query: Query<(Entity, &mut TimelineDialogue)>,
+) {
+    for (entity, mut timer) in &mut dialogue_query {
+        if timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! A dotted arc showing where the player will fly after letting go of a chain or jumping.
+
+use crate::game::chain::ConnectedChain;
+use crate::game::death_anim::PauseWhenDyingSystems;
+use crate::game::enemy::has_line_of_sight;
+use crate::game::movement::Grounded;
+use crate::game::player::Player;
+use crate::game::tuning::GameplayTuning;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::util::trajectory;
+
+const PREVIEW_DOTS: usize = 16;
+/// Seconds of flight between each dot.
+const PREVIEW_INTERVAL: f32 = 0.06;
+const PREVIEW_DOT_SIZE: f32 = 3.0;
+const PREVIEW_ALPHA: f32 = 0.6;
+const PREVIEW_Z: f32 = 3.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TrajectoryDot>();
+
+    app.add_systems(
+        StateFlush,
+        Screen::Gameplay.on_enter(spawn_trajectory_preview),
+    );
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update(update_trajectory_preview)
+            .in_set(PausableSystems)
+            .in_set(PauseWhenDyingSystems),
+    );
+}
+
+/// One dot along the arc, by its index from the player.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+struct TrajectoryDot(usize);
+
+fn spawn_trajectory_preview(mut commands: Commands) {
+    for i in 0..PREVIEW_DOTS {
+        // Fade out towards the end of the arc.
+        let alpha = PREVIEW_ALPHA * (1.0 - i as f32 / PREVIEW_DOTS as f32);
+        commands.spawn((
+            Name::new("TrajectoryDot"),
+            TrajectoryDot(i),
+            Sprite::from_color(
+                Color::WHITE.with_alpha(alpha),
+                Vec2::splat(PREVIEW_DOT_SIZE),
+            ),
+            Visibility::Hidden,
+            DespawnOnExitState::<Screen>::default(),
+        ));
+    }
+}
+
+fn update_trajectory_preview(
+    player: Single<
+        (
+            &GlobalTransform,
+            &LinearVelocity,
+            Option<&GravityScale>,
+            Has<ConnectedChain>,
+            Has<Grounded>,
+        ),
+        With<Player>,
+    >,
+    gravity: Res<Gravity>,
+    tuning: ConfigRef<GameplayTuning>,
+    spatial_query: SpatialQuery,
+    mut dot_query: Query<(&TrajectoryDot, &mut Transform, &mut Visibility)>,
+) {
+    let (transform, velocity, gravity_scale, is_on_chain, is_grounded) = *player;
+    let tuning = r!(tuning.get());
+
+    // Letting go of a chain switches back to the normal gravity scale.
+    let gravity = if is_on_chain {
+        trajectory::body_gravity(&gravity, Some(&tuning.player.gravity_scale(false)))
+    } else {
+        trajectory::body_gravity(&gravity, gravity_scale)
+    };
+    let start = transform.translation().xy();
+    let points = trajectory::sample(start, velocity.0, gravity, PREVIEW_INTERVAL, PREVIEW_DOTS)
+        .collect::<Vec<_>>();
+
+    // Stop the arc at the first wall it runs into.
+    let mut shown_dots = 0;
+    if is_on_chain || !is_grounded {
+        let mut previous = start;
+        for &point in &points {
+            if !has_line_of_sight(&spatial_query, previous, point) {
+                break;
+            }
+            previous = point;
+            shown_dots += 1;
+        }
+    }
+
+    for (dot, mut transform, mut visibility) in &mut dot_query {
+        transform.translation = c!(points.get(dot.0)).extend(PREVIEW_Z);
+        *visibility = if dot.0 < shown_dots {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
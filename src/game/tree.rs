@@ -1,13 +1,25 @@
 use bevy_ecs_ldtk::prelude::*;
 
 use crate::game::chain_movement::GameLayer;
-use crate::game::death_anim::PlayerDeath;
-use crate::game::player::Player;
+use crate::game::enemy::Aim;
+use crate::game::enemy::EnemyAttack;
+use crate::game::enemy::EnemyBundle;
+use crate::game::enemy::EnemySystems;
+use crate::game::fire::BurntOut;
+use crate::game::fire::Flammable;
+use crate::game::projectile::Projectile;
+use crate::game::projectile::ShootProjectile;
 use crate::game::vines::KillsPlayer;
-use crate::game::vines::on_collision_kills_player;
+use crate::game::vines::on_collision_damages_player;
 use crate::prelude::*;
 use crate::screen::Screen;
 
+/// Where apples are thrown from, relative to the tree.
+const APPLE_OFFSET: Vec2 = Vec2::new(0.0, 5.0);
+const APPLE_SPEED: f32 = 350.0;
+/// Seconds before an apple that missed is cleaned up.
+const APPLE_LIFETIME: f32 = 8.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.register_ldtk_entity::<TreeBundle>("tree");
 
@@ -15,7 +27,7 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        Screen::Gameplay.on_update((process_tree, process_apple_timer)),
+        Screen::Gameplay.on_update(process_tree.before(EnemySystems)),
     );
 }
 
@@ -23,10 +35,6 @@ pub(super) fn plugin(app: &mut App) {
 #[reflect(Component)]
 pub struct Tree;
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut, Reflect)]
-#[reflect(Component)]
-pub struct AppleTimer(pub Timer);
-
 #[derive(AssetCollection, Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct TreeAssets {
@@ -44,6 +52,8 @@ impl Configure for TreeAssets {
 #[derive(Bundle, Default, LdtkEntity)]
 struct TreeBundle {
     tree: Tree,
+    #[ldtk_entity]
+    enemy: EnemyBundle,
     #[sprite_sheet]
     sprite: Sprite,
     kills_player: KillsPlayer,
@@ -57,77 +67,60 @@ fn process_tree(tree_added_query: Query<Entity, Added<Tree>>, mut commands: Comm
     for tree_entity in tree_added_query {
         commands
             .entity(tree_entity)
-            .insert(Collider::rectangle(80.0, 80.0))
-            .insert(AppleTimer(Timer::from_seconds(5.0, TimerMode::Repeating)))
+            .insert((Name::new("Tree"), Collider::rectangle(80.0, 80.0)))
+            .insert(Flammable {
+                fuel: 5.0,
+                half_size: Vec2::splat(40.0),
+            })
             .insert(CollisionLayers::new(
                 GameLayer::TreeLayer,
                 LayerMask::DEFAULT,
             ))
-            .observe(on_collision_kills_player);
+            .observe(on_collision_damages_player)
+            .observe(on_tree_burnt_out)
+            .observe(on_tree_attack);
     }
 }
 
-fn process_apple_timer(
-    apple_timer_query: Query<(&mut AppleTimer, &GlobalTransform)>,
-    player_transform: Single<&GlobalTransform, With<Player>>,
-    mut commands: Commands,
-    time: Res<Time>,
-    tree_assets: Res<TreeAssets>,
-) {
-    for (mut apple_timer, transform) in apple_timer_query {
-        apple_timer.tick(time.delta());
-        if apple_timer.just_finished() {
-            let x = 6.0 * (random::<f32>() - 0.5);
-            let y = 6.0 * (random::<f32>() - 0.5) + 5.0;
-            let apple_pos = transform.translation() + Vec3::new(x, y, 0.0);
-
-            let direction = player_transform.translation().xy() - apple_pos.xy();
-            let x_velocity = f32::min(direction.x, 3000.0);
-            let y_velocity = 140.0;
-            let linear_velocity = LinearVelocity(Vec2::new(x_velocity, y_velocity));
-
-            commands
-                .spawn((
-                    Apple,
-                    Sprite {
-                        image: tree_assets.apple_image.clone(),
-                        custom_size: Some(Vec2::splat(10.0)),
-                        ..default()
-                    },
-                    Transform::from_translation(apple_pos),
-                    linear_velocity,
-                    AngularVelocity(0.1),
-                    Collider::circle(2.0),
-                    RigidBody::Dynamic,
-                    CollisionEventsEnabled,
-                    CollisionLayers::new(
-                        LayerMask::DEFAULT,
-                        LayerMask::DEFAULT & !(GameLayer::TreeLayer.to_bits()),
-                    ),
-                ))
-                .observe(on_apple_collision);
-        }
-    }
+fn on_tree_burnt_out(trigger: Trigger<BurntOut>, mut commands: Commands) {
+    commands.entity(trigger.target()).despawn();
 }
 
-fn on_apple_collision(
-    trigger: Trigger<OnCollisionStart>,
-    player_query: Query<Entity, With<Player>>,
-    tree_query: Query<Entity, With<Tree>>,
-    mut death_event_writer: EventWriter<PlayerDeath>,
-    mut commands: Commands,
+fn on_tree_attack(
+    trigger: Trigger<EnemyAttack>,
+    tree_query: Query<(&GlobalTransform, &Aim), With<Tree>>,
+    gravity: Res<Gravity>,
+    tree_assets: Res<TreeAssets>,
+    mut shoot_event_writer: EventWriter<ShootProjectile>,
 ) {
-    let apple_entity = trigger.target();
-    let other_entity = trigger.collider;
+    let tree_entity = trigger.target();
+    let (transform, aim) = r!(tree_query.get(tree_entity));
+    let start = transform.translation().xy() + APPLE_OFFSET;
+    let velocity = aim.launch_velocity(start, trigger.event(), APPLE_SPEED, gravity.0);
+    shoot_event_writer.write(apple(&tree_assets, tree_entity, start, velocity));
+}
 
-    if player_query.contains(other_entity) {
-        death_event_writer.write(PlayerDeath);
-    } else if !tree_query.contains(other_entity) {
-        // delete apple if we collide with anything else
-        commands.entity(apple_entity).despawn();
+/// An apple thrown by `owner` that hurts the player on contact.
+pub fn apple(
+    tree_assets: &TreeAssets,
+    owner: Entity,
+    start: Vec2,
+    velocity: Vec2,
+) -> ShootProjectile {
+    ShootProjectile {
+        name: Name::new("Apple"),
+        projectile: Projectile::new(owner, APPLE_LIFETIME).with_layers(CollisionLayers::new(
+            LayerMask::DEFAULT,
+            LayerMask::DEFAULT & !(GameLayer::TreeLayer.to_bits()),
+        )),
+        sprite: Sprite {
+            image: tree_assets.apple_image.clone(),
+            custom_size: Some(Vec2::splat(10.0)),
+            ..default()
+        },
+        collider: Collider::circle(2.0),
+        position: start,
+        velocity,
+        angular_velocity: 0.1,
     }
 }
-
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
-#[reflect(Component)]
-pub struct Apple;
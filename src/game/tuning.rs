@@ -0,0 +1,118 @@
+use avian2d::math::Scalar;
+use avian2d::math::Vector;
+
+use crate::game::chain::ConnectedChain;
+use crate::game::movement::CharacterController;
+use crate::game::movement::MovementBundle;
+use crate::game::player::Player;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<ConfigHandle<GameplayTuning>>();
+
+    app.add_systems(
+        Update,
+        state!(Screen::Title | Screen::Loading)
+            .on_update(GameplayTuning::progress.track_progress::<BevyState<Screen>>()),
+    );
+}
+
+/// Movement and physics values for gameplay, hot-reloaded from `config/gameplay.ron`.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GameplayTuning {
+    /// Downwards acceleration applied to every rigid body.
+    pub gravity: Scalar,
+    /// The physics length unit, used to scale tolerances.
+    pub pixels_per_meter: Scalar,
+    pub movement: MovementTuning,
+    pub player: PlayerTuning,
+}
+
+impl Config for GameplayTuning {
+    const FILE: &'static str = "gameplay.ron";
+
+    fn on_load(&self, world: &mut World) {
+        world.insert_resource(Gravity(Vector::NEG_Y * self.gravity));
+        world.insert_resource(PhysicsLengthUnit(self.pixels_per_meter));
+
+        // Re-apply to anything that's already spawned so changes show up immediately.
+        let controllers = world
+            .query_filtered::<Entity, With<CharacterController>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        for entity in controllers {
+            world.entity_mut(entity).insert(self.movement.bundle());
+        }
+
+        let players = world
+            .query_filtered::<(Entity, Has<ConnectedChain>), With<Player>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        for (entity, is_on_chain) in players {
+            world.entity_mut(entity).insert((
+                self.player.physics_bundle(),
+                self.player.gravity_scale(is_on_chain),
+            ));
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MovementTuning {
+    pub acceleration: Scalar,
+    /// Horizontal velocity multiplier applied every fixed step.
+    pub damping: Scalar,
+    pub jump_impulse: Scalar,
+    pub max_slope_angle_degrees: Scalar,
+    pub max_velocity: Scalar,
+    pub max_wall_slide_speed: Scalar,
+    pub wall_jump_impulse: Scalar,
+    pub wall_jump_angle_degrees: Scalar,
+}
+
+impl MovementTuning {
+    pub fn bundle(&self) -> MovementBundle {
+        MovementBundle::new(
+            self.acceleration,
+            self.damping,
+            self.jump_impulse,
+            self.max_slope_angle_degrees.to_radians(),
+            self.max_velocity,
+            self.max_wall_slide_speed,
+            self.wall_jump_impulse,
+            self.wall_jump_angle_degrees.to_radians(),
+        )
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerTuning {
+    pub gravity_scale: Scalar,
+    /// Gravity scale while attached to a chain.
+    pub chain_gravity_scale: Scalar,
+    pub friction: Scalar,
+    pub restitution: Scalar,
+    pub density: Scalar,
+}
+
+impl PlayerTuning {
+    pub fn physics_bundle(&self) -> impl Bundle {
+        (
+            Friction::new(self.friction).with_combine_rule(CoefficientCombine::Min),
+            Restitution::new(self.restitution).with_combine_rule(CoefficientCombine::Min),
+            ColliderDensity(self.density),
+        )
+    }
+
+    pub fn gravity_scale(&self, is_on_chain: bool) -> GravityScale {
+        GravityScale(if is_on_chain {
+            self.chain_gravity_scale
+        } else {
+            self.gravity_scale
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use bevy_ecs_ldtk::prelude::*;
 
-use crate::game::death_anim::PlayerDeath;
+use crate::game::fire::FireCellBurntOut;
+use crate::game::health::PlayerDamage;
 use crate::game::player::Player;
 use crate::prelude::*;
 use crate::screen::Screen;
@@ -11,7 +12,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
         Screen::Gameplay
-            .on_update(process_vines)
+            .on_update((process_vines, burn_vines))
             .in_set(PausableSystems),
     );
 }
@@ -38,18 +39,49 @@ fn process_vines(vines_added_query: Query<Entity, Added<Vine>>, mut commands: Co
     for vine_entity in vines_added_query {
         commands
             .entity(vine_entity)
-            .insert(Collider::rectangle(5.0, 2.0))
-            .observe(on_collision_kills_player);
+            .insert((Name::new("Vines"), Collider::rectangle(5.0, 2.0)))
+            .observe(on_collision_damages_player);
     }
 }
 
-pub fn on_collision_kills_player(
+pub fn on_collision_damages_player(
     trigger: Trigger<OnCollisionStart>,
     player_query: Query<Entity, With<Player>>,
-    mut death_event_writer: EventWriter<PlayerDeath>,
+    transform_query: Query<&GlobalTransform>,
+    name_query: Query<&Name>,
+    mut damage_event_writer: EventWriter<PlayerDamage>,
 ) {
+    let hazard_entity = trigger.target();
     let other_entity = trigger.collider;
     if player_query.contains(other_entity) {
-        death_event_writer.write(PlayerDeath);
+        damage_event_writer.write(PlayerDamage {
+            source: hazard_entity,
+            cause: name_query
+                .get(hazard_entity)
+                .cloned()
+                .unwrap_or_else(|_| Name::new("Unknown")),
+            position: r!(transform_query.get(hazard_entity)).translation().xy(),
+        });
+    }
+}
+
+/// Removes vines once the fire on them has burnt out.
+fn burn_vines(
+    mut commands: Commands,
+    mut burnt_out_event_reader: EventReader<FireCellBurntOut>,
+    vine_query: Query<(Entity, &GridCoords), With<Vine>>,
+) {
+    let burnt_out = burnt_out_event_reader
+        .read()
+        .map(|x| x.coords)
+        .collect::<HashSet<_>>();
+    if burnt_out.is_empty() {
+        return;
+    }
+
+    for (entity, coords) in &vine_query {
+        if burnt_out.contains(coords) {
+            commands.entity(entity).despawn();
+        }
     }
 }
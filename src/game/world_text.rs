@@ -1,14 +1,29 @@
+//! Signs placed in the level, with rich text that fades in as the player gets close.
+
 use bevy_ecs_ldtk::prelude::*;
 
+use crate::game::player::Player;
+use crate::game::player::PlayerAction;
 use crate::prelude::*;
 use crate::screen::Screen;
 
+const WORLD_TEXT_SIZE: f32 = 20.0;
+/// How much of a fade happens per second.
+const FADE_SPEED: f32 = 2.0;
+
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(WorldText, ProximityFade)>();
+
     app.register_ldtk_entity::<TempTextBundle>("text");
 
-    app.add_systems(Update, Screen::Gameplay.on_update(process_text));
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update((process_text, update_proximity_fade.in_set(PausableSystems)).chain()),
+    );
 }
 
+/// Rich text markup, where `{action:Jump}` shows whatever [`PlayerAction::Jump`] is bound to.
 #[derive(Component, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut, Reflect)]
 #[reflect(Component)]
 pub struct WorldText(pub String);
@@ -19,23 +34,168 @@ impl WorldText {
     }
 }
 
+/// Fades the text in while the player is within the radius, and out again when they leave.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct ProximityFade {
+    /// A radius of 0 keeps the text shown from anywhere.
+    pub radius: f32,
+    alpha: f32,
+}
+
+impl ProximityFade {
+    pub fn new(radius: f32) -> Self {
+        Self { radius, alpha: 0.0 }
+    }
+
+    fn from_entity_instance(entity_instance: &EntityInstance) -> Self {
+        Self::new(
+            entity_instance
+                .get_float_field("radius")
+                .map_or(0.0, |&x| x),
+        )
+    }
+}
+
 #[derive(Bundle, Default, LdtkEntity)]
 struct TempTextBundle {
     #[with(WorldText::from_entity_instance)]
     world_text: WorldText,
+    #[with(ProximityFade::from_entity_instance)]
+    proximity_fade: ProximityFade,
+}
+
+fn process_text(
+    mut commands: Commands,
+    text_query: Query<(Entity, Ref<WorldText>)>,
+    input_map: Res<InputMap<PlayerAction>>,
+    gamepad_query: Query<(), With<Gamepad>>,
+    mut used_gamepad: Local<bool>,
+) {
+    // Rebinding or plugging in a gamepad changes which inputs the text should show.
+    let use_gamepad = !gamepad_query.is_empty();
+    let inputs_changed = input_map.is_changed() || use_gamepad != *used_gamepad;
+    *used_gamepad = use_gamepad;
+
+    for (text_entity, world_text) in &text_query {
+        if !inputs_changed && !world_text.is_changed() {
+            continue;
+        }
+
+        let sections = parse_rich(expand_actions(&world_text, &input_map, use_gamepad));
+        commands
+            .entity(text_entity)
+            .despawn_related::<Children>()
+            .insert(Text2d::default())
+            .with_children(|parent| {
+                for section in sections {
+                    parent.spawn((
+                        TextSpan::new(section.value),
+                        TextFont {
+                            font: section.style.font,
+                            font_size: WORLD_TEXT_SIZE,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                }
+            });
+    }
+}
+
+/// Replaces each `{action:Name}` with the input bound to that [`PlayerAction`].
+///
+/// Unknown actions are left as literal text.
+fn expand_actions(text: &str, input_map: &InputMap<PlayerAction>, use_gamepad: bool) -> String {
+    regex_replace_all!(
+        r"\{action:(\w+)\}",
+        text,
+        |placeholder: &str, name: &str| {
+            PlayerAction::ALL
+                .into_iter()
+                .find(|action| format!("{action:?}") == name)
+                .and_then(|action| input_map.get_buttonlike(&action))
+                .and_then(|inputs| {
+                    // Fall back to any input if there's nothing bound on the device being used.
+                    let is_gamepad = |input: &&Box<dyn Buttonlike>| {
+                        input.as_any().is::<GamepadButton>() == use_gamepad
+                    };
+                    inputs.iter().find(is_gamepad).or(inputs.first())
+                })
+                .map_or_else(|| placeholder.to_string(), |input| input_name(&**input))
+        }
+    )
+    .into_owned()
+}
+
+/// A short name for an input, like `W` for [`KeyCode::KeyW`].
+fn input_name(input: &dyn Buttonlike) -> String {
+    let input = input.as_any();
+    if let Some(key) = input.downcast_ref::<KeyCode>() {
+        let name = format!("{key:?}");
+        ["Key", "Digit", "Arrow"]
+            .into_iter()
+            .find_map(|prefix| name.strip_prefix(prefix))
+            .unwrap_or(&name)
+            .to_string()
+    } else if let Some(button) = input.downcast_ref::<MouseButton>() {
+        format!("{button:?} Click")
+    } else if let Some(button) = input.downcast_ref::<GamepadButton>() {
+        format!("{button:?}")
+    } else {
+        "?".to_string()
+    }
 }
 
-fn process_text(text_query: Query<(Entity, Ref<WorldText>)>, mut commands: Commands) {
-    for (text_entity, world_text) in text_query {
-        if world_text.is_added() {
-            commands.entity(text_entity).insert((
-                Text2d::new((*world_text).to_string()),
-                TextFont {
-                    font: FONT_HANDLE,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-            ));
+fn update_proximity_fade(
+    time: Res<Time>,
+    player: Single<&GlobalTransform, With<Player>>,
+    mut fade_query: Query<(&mut ProximityFade, &GlobalTransform, &Children)>,
+    mut color_query: Query<&mut TextColor>,
+) {
+    let player_pos = player.translation().xy();
+    let step = FADE_SPEED * time.delta_secs();
+
+    for (mut fade, transform, children) in &mut fade_query {
+        let is_near =
+            fade.radius <= 0.0 || transform.translation().xy().distance(player_pos) <= fade.radius;
+        let target = if is_near { 1.0 } else { 0.0 };
+        fade.alpha += (target - fade.alpha).clamp(-step, step);
+
+        for &child in children {
+            let mut color = c!(color_query.get_mut(child));
+            if color.0.alpha() != fade.alpha {
+                color.0.set_alpha(fade.alpha);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_actions() {
+        let input_map = InputMap::default()
+            .with(PlayerAction::Jump, KeyCode::Space)
+            .with(PlayerAction::Jump, GamepadButton::South)
+            .with(PlayerAction::Left, KeyCode::KeyA)
+            .with(PlayerAction::Grapple, MouseButton::Left);
+
+        for (case, use_gamepad, want) in [
+            ("Press {action:Jump}", false, "Press Space"),
+            ("Press {action:Jump}", true, "Press South"),
+            ("{action:Left}/{action:Right}", false, "A/{action:Right}"),
+            ("{action:Left}", true, "A"),
+            ("{action:Grapple}", false, "Left Click"),
+            (
+                "{action:Dance} {action:}",
+                false,
+                "{action:Dance} {action:}",
+            ),
+        ] {
+            assert_eq!(expand_actions(case, &input_map, use_gamepad), want);
         }
     }
 }
@@ -1,3 +1,8 @@
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::health::Difficulty;
+use crate::game::health::Lives;
+use crate::game::run_stats::RunStats;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
@@ -8,7 +13,25 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::Death.on_enter(spawn_death_menu));
 }
 
-fn spawn_death_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+fn spawn_death_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    difficulty: Res<Difficulty>,
+    lives: Res<Lives>,
+) {
+    if difficulty.has_lives() && lives.0 == 0 {
+        commands
+            .entity(menu_root.ui)
+            .with_child(widget::body(children![
+                widget::header("[b]Out of lives"),
+                widget::column_of_buttons(children![
+                    widget::wide_button("Start over", start_over),
+                    widget::wide_button("Quit to title screen", quit_to_title),
+                ])
+            ]));
+        return;
+    }
+
     commands
         .entity(menu_root.ui)
         .with_child(widget::body(children![
@@ -24,6 +47,19 @@ fn last_checkpoint(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.spawn(fade_out(Screen::Gameplay));
 }
 
+fn start_over(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    *lives = default();
+    *run_stats = default();
+    *level_selection = LevelSelection::index(0);
+    commands.spawn(fade_out(Screen::Gameplay));
+}
+
 fn quit_to_title(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.spawn(fade_out(Screen::Title));
 }
@@ -0,0 +1,262 @@
+use crate::game::dialogue::ActiveDialogue;
+use crate::game::dialogue::Dialogue;
+use crate::game::dialogue::DialogueFlags;
+use crate::game::dialogue::DialogueNext;
+use crate::game::player::PlayerAction;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(DialogueText, DialogueChoices, DialogueChoiceButton)>();
+
+    app.add_systems(StateFlush, Menu::Dialogue.on_enter(spawn_dialogue_menu));
+    app.add_systems(
+        Update,
+        Menu::Dialogue.on_update((
+            advance_dialogue
+                .in_set(UpdateSystems::RecordInput)
+                .run_if(action_just_pressed(PlayerAction::Interact)),
+            (reveal_dialogue_text, show_dialogue_choices)
+                .chain()
+                .in_set(UpdateSystems::Update),
+        )),
+    );
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueText;
+
+/// Holds the buttons for the choices on a page, once its text has been revealed.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct DialogueChoices {
+    /// The page whose choices are shown.
+    page: Option<usize>,
+}
+
+/// The index of a choice on the current page.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueChoiceButton(usize);
+
+fn spawn_dialogue_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    active_dialogue: Res<ActiveDialogue>,
+    dialogues: Res<Assets<Dialogue>>,
+    asset_server: Res<AssetServer>,
+) {
+    let dialogue = r!(dialogues.get(&active_dialogue.dialogue));
+    let portrait = dialogue.portrait.as_ref().map(|path| {
+        (
+            Name::new("Portrait"),
+            ImageNode::new(asset_server.load(path)),
+            Node {
+                width: Vw(10.0),
+                height: Vw(10.0),
+                flex_shrink: 0.0,
+                ..default()
+            },
+        )
+    });
+
+    commands.entity(menu_root.ui).with_child((
+        Name::new("DialogueMenu"),
+        Node {
+            align_items: AlignItems::End,
+            justify_content: JustifyContent::Center,
+            padding: UiRect::bottom(Vw(5.0)),
+            ..Node::DEFAULT.full_size()
+        },
+        Pickable::IGNORE,
+        children![(
+            Name::new("DialogueBox"),
+            Node {
+                width: Vw(70.0),
+                padding: UiRect::all(Vw(2.0)),
+                column_gap: Vw(2.0),
+                ..Node::ROW_MID
+            },
+            ThemeColor::Popup.set::<BackgroundColor>(),
+            Children::spawn((
+                SpawnIter(portrait.into_iter()),
+                Spawn((
+                    Name::new("DialogueContent"),
+                    Node {
+                        flex_grow: 1.0,
+                        row_gap: Vw(1.0),
+                        ..Node::COLUMN_LEFT
+                    },
+                    children![
+                        widget::small_label(format!("[b]{}", dialogue.speaker)),
+                        (DialogueText, widget::label("")),
+                        (
+                            Name::new("DialogueChoices"),
+                            DialogueChoices::default(),
+                            Node {
+                                row_gap: Vw(1.0),
+                                ..Node::COLUMN_MID
+                            },
+                        ),
+                    ],
+                )),
+            )),
+        )],
+    ));
+}
+
+/// Shows the rest of the page if it's still being revealed, or else moves on to the next page.
+fn advance_dialogue(
+    dialogues: Res<Assets<Dialogue>>,
+    flags: Res<DialogueFlags>,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    mut menu: NextMut<Menu>,
+) {
+    let dialogue = r!(dialogues.get(&active_dialogue.dialogue));
+    let page = r!(dialogue.pages.get(active_dialogue.page));
+
+    let length = text_length(&parse_rich(&page.text));
+    if (active_dialogue.revealed as usize) < length {
+        active_dialogue.revealed = length as f32;
+        return;
+    }
+    // Pages with choices wait for one to be picked.
+    if !page.choices.is_empty() {
+        return;
+    }
+
+    turn_page(
+        dialogue,
+        DialogueNext::Next,
+        &flags,
+        &mut active_dialogue,
+        &mut menu,
+    );
+}
+
+fn reveal_dialogue_text(
+    time: Res<Time>,
+    dialogues: Res<Assets<Dialogue>>,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    mut text: Single<&mut RichText, With<DialogueText>>,
+    mut shown: Local<(usize, usize)>,
+) {
+    let dialogue = r!(dialogues.get(&active_dialogue.dialogue));
+    let page = r!(dialogue.pages.get(active_dialogue.page));
+    let sections = parse_rich(&page.text);
+
+    let length = text_length(&sections) as f32;
+    active_dialogue.revealed =
+        (active_dialogue.revealed + dialogue.speed * time.delta_secs()).min(length);
+
+    // Only replace the text when another character has been revealed.
+    let count = active_dialogue.revealed as usize;
+    if *shown == (active_dialogue.page, count) && !text.is_added() {
+        return;
+    }
+    *shown = (active_dialogue.page, count);
+    text.sections = reveal(&sections, count);
+}
+
+fn show_dialogue_choices(
+    mut commands: Commands,
+    dialogues: Res<Assets<Dialogue>>,
+    active_dialogue: Res<ActiveDialogue>,
+    choices: Single<(Entity, &mut DialogueChoices)>,
+) {
+    let dialogue = r!(dialogues.get(&active_dialogue.dialogue));
+    let page = r!(dialogue.pages.get(active_dialogue.page));
+    let is_revealed = active_dialogue.revealed as usize >= text_length(&parse_rich(&page.text));
+    let page_to_show = is_revealed.then_some(active_dialogue.page);
+
+    let (entity, mut choices) = choices.into_inner();
+    if choices.page == page_to_show {
+        return;
+    }
+    choices.page = page_to_show;
+
+    let buttons = page_to_show
+        .into_iter()
+        .flat_map(|_| page.choices.iter().enumerate())
+        .map(|(i, choice)| {
+            (
+                widget::wide_button(choice.text.clone(), pick_choice),
+                DialogueChoiceButton(i),
+            )
+        })
+        .collect::<Vec<_>>();
+    commands
+        .entity(entity)
+        .despawn_related::<Children>()
+        .insert(Children::spawn(SpawnIter(buttons.into_iter())));
+}
+
+fn pick_choice(
+    trigger: Trigger<Pointer<Click>>,
+    button_query: Query<&DialogueChoiceButton>,
+    dialogues: Res<Assets<Dialogue>>,
+    mut flags: ResMut<DialogueFlags>,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    mut menu: NextMut<Menu>,
+) {
+    let index = r!(button_query.get(trigger.target())).0;
+    let dialogue = r!(dialogues.get(&active_dialogue.dialogue));
+    let choice = r!(dialogue
+        .pages
+        .get(active_dialogue.page)
+        .and_then(|page| page.choices.get(index)));
+
+    if let Some(flag) = &choice.sets {
+        flags.insert(flag.clone());
+    }
+    turn_page(
+        dialogue,
+        choice.then,
+        &flags,
+        &mut active_dialogue,
+        &mut menu,
+    );
+}
+
+/// Moves on to the next page to show, or closes the dialogue if there isn't one.
+fn turn_page(
+    dialogue: &Dialogue,
+    next: DialogueNext,
+    flags: &DialogueFlags,
+    active_dialogue: &mut ActiveDialogue,
+    menu: &mut NextMut<Menu>,
+) {
+    match dialogue.follow(active_dialogue.page, next, flags) {
+        Some(page) => {
+            active_dialogue.page = page;
+            active_dialogue.revealed = 0.0;
+        },
+        None => menu.disable(),
+    }
+}
+
+fn text_length(sections: &[TextSection]) -> usize {
+    sections.iter().map(|x| x.value.chars().count()).sum()
+}
+
+/// The first `count` characters of some rich text.
+fn reveal(sections: &[TextSection], mut count: usize) -> Vec<TextSection> {
+    let mut revealed = vec![];
+    for section in sections {
+        if count == 0 {
+            break;
+        }
+
+        let mut section = section.clone();
+        let length = section.value.chars().count();
+        if let Some((end, _)) = section.value.char_indices().nth(count) {
+            section.value.truncate(end);
+        }
+        count = count.saturating_sub(length);
+        revealed.push(section);
+    }
+
+    revealed
+}
@@ -1,3 +1,5 @@
+use crate::game::run_stats::RunStats;
+use crate::game::run_stats::format_time;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
@@ -8,11 +10,68 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::GameOver.on_enter(spawn_gameover_menu));
 }
 
-fn spawn_gameover_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+fn spawn_gameover_menu(mut commands: Commands, menu_root: Res<MenuRoot>, run_stats: Res<RunStats>) {
+    let best = &run_stats.previous_best;
+    let total_time = run_stats.total_time();
+    let total_deaths = run_stats.total_deaths();
+    // Totals only count towards a personal best when every level was played.
+    let (time_comparison_text, deaths_comparison_text) = if run_stats.played_every_level() {
+        (
+            time_comparison(total_time, best.total_time),
+            deaths_comparison(total_deaths, best.total_deaths),
+        )
+    } else {
+        default()
+    };
+
+    let level_rows = run_stats
+        .levels
+        .iter()
+        .enumerate()
+        // Skip levels that weren't played, like when starting from a later level.
+        .filter(|(_, level)| level.time > 0.0)
+        .map(|(i, level)| {
+            let collectibles = &level.collectibles;
+            let mut row = format!(
+                "[b]Level {}[r]  {} {}[r], {} deaths, {}/{} collected",
+                i + 1,
+                format_time(level.time),
+                time_comparison(level.time, best.level_times.get(i).copied().flatten()),
+                level.deaths,
+                collectibles.required.collected + collectibles.optional.collected,
+                collectibles.required.total + collectibles.optional.total,
+            );
+            if collectibles.secret.total > 0 {
+                row += &format!(
+                    ", {}/{} secrets",
+                    collectibles.secret.collected, collectibles.secret.total,
+                );
+            }
+            widget::label(row)
+        })
+        .collect::<Vec<_>>();
+
     commands
         .entity(menu_root.ui)
         .with_child(widget::body(children![
-            widget::header("[b]You won?"),
+            widget::header("[b]Results"),
+            widget::big_label(format!(
+                "{} {time_comparison_text}",
+                format_time(total_time),
+            )),
+            widget::label(format!(
+                "{total_deaths} deaths {deaths_comparison_text}[r], {} points",
+                run_stats.total_points(),
+            )),
+            (
+                Name::new("LevelResults"),
+                Node {
+                    margin: UiRect::vertical(Vw(2.5)),
+                    row_gap: Vw(1.4),
+                    ..Node::COLUMN_MID
+                },
+                Children::spawn(SpawnIter(level_rows.into_iter())),
+            ),
             widget::column_of_buttons(children![widget::wide_button(
                 "Back to title screen",
                 quit_to_title
@@ -20,6 +79,24 @@ fn spawn_gameover_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         ]));
 }
 
+/// Describes a time relative to the previous best, where lower is better.
+fn time_comparison(time: f32, best: Option<f32>) -> String {
+    match best {
+        None => "[b](new best)".to_string(),
+        Some(best) if time < best => format!("[b](new best, -{:.2}s)", best - time),
+        Some(best) => format!("(+{:.2}s)", time - best),
+    }
+}
+
+/// Describes a death count relative to the previous best, where lower is better.
+fn deaths_comparison(deaths: u32, best: Option<u32>) -> String {
+    match best {
+        None => "[b](new best)".to_string(),
+        Some(best) if deaths < best => format!("[b](new best, -{})", best - deaths),
+        Some(best) => format!("(best {best})"),
+    }
+}
+
 fn quit_to_title(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.spawn(fade_out(Screen::Title));
 }
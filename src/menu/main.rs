@@ -1,10 +1,25 @@
+use bevy_simple_prefs::Prefs;
+use bevy_simple_prefs::PrefsPlugin;
+
+use crate::game::death_anim::DeathSettings;
+use crate::game::health::Difficulty;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
 use crate::screen::Screen;
 use crate::screen::fade::fade_out;
 
+/// Where settings are saved: a file on native, or local storage on web.
+const SETTINGS_FILE: &str = "settings.ron";
+
 pub(super) fn plugin(app: &mut App) {
+    app.configure::<SettingSelector>();
+
+    app.add_plugins(PrefsPlugin::<SavedSettings> {
+        filename: SETTINGS_FILE.to_string(),
+        ..default()
+    });
+
     app.add_systems(StateFlush, Menu::Main.on_enter(spawn_main_menu));
 }
 
@@ -16,6 +31,17 @@ fn spawn_main_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
             widget::header("[b]Chain Game"),
             widget::column_of_buttons(children![
                 widget::big_button("Play", start_game),
+                widget::selector(
+                    SettingSelector::Difficulty,
+                    cycle_difficulty,
+                    cycle_difficulty,
+                ),
+                widget::selector(
+                    SettingSelector::Respawn,
+                    toggle_quick_respawn,
+                    toggle_quick_respawn,
+                ),
+                widget::selector(SettingSelector::Ragdoll, toggle_ragdoll, toggle_ragdoll),
                 (
                     widget::big_button("Quit", quit_to_desktop),
                     #[cfg(feature = "web")]
@@ -25,6 +51,13 @@ fn spawn_main_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         ]));
 }
 
+/// Settings that are kept across sessions.
+#[derive(Prefs, Reflect, Default)]
+struct SavedSettings {
+    difficulty: Difficulty,
+    death_settings: DeathSettings,
+}
+
 fn start_game(
     _: Trigger<Pointer<Click>>,
     mut commands: Commands,
@@ -38,6 +71,87 @@ fn start_game(
     }));
 }
 
+fn cycle_difficulty(_: Trigger<Pointer<Click>>, mut difficulty: ResMut<Difficulty>) {
+    *difficulty = difficulty.next();
+}
+
+fn toggle_quick_respawn(_: Trigger<Pointer<Click>>, mut settings: ResMut<DeathSettings>) {
+    settings.quick_respawn = !settings.quick_respawn;
+}
+
+fn toggle_ragdoll(_: Trigger<Pointer<Click>>, mut settings: ResMut<DeathSettings>) {
+    settings.ragdoll = !settings.ragdoll;
+}
+
+/// A selector that shows the current value of a setting.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+enum SettingSelector {
+    Difficulty,
+    Respawn,
+    Ragdoll,
+}
+
+impl Configure for SettingSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            update_setting_selectors.in_set(UpdateSystems::Update),
+        );
+    }
+}
+
+impl SettingSelector {
+    fn label(self, difficulty: Difficulty, death_settings: DeathSettings) -> String {
+        match self {
+            Self::Difficulty => format!("Difficulty: {}", difficulty.name()),
+            Self::Respawn => format!(
+                "Respawn: {}",
+                if death_settings.quick_respawn {
+                    "Quick"
+                } else {
+                    "Menu"
+                },
+            ),
+            Self::Ragdoll => format!(
+                "Ragdoll: {}",
+                if death_settings.ragdoll { "On" } else { "Off" },
+            ),
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_setting_selectors(
+    difficulty: Res<Difficulty>,
+    death_settings: Res<DeathSettings>,
+    selector_query: Query<(&SettingSelector, &Children)>,
+    children_query: Query<&Children>,
+    mut label_query: Query<&mut RichText>,
+) {
+    for (selector, children) in &selector_query {
+        let display = selector.label(*difficulty, *death_settings);
+        set_selector_label(children, &children_query, &mut label_query, &display);
+    }
+}
+
+fn set_selector_label(
+    selector_children: &Children,
+    children_query: &Query<&Children>,
+    label_query: &mut Query<&mut RichText>,
+    display: &str,
+) {
+    // The label is inside the stretch between the two buttons.
+    let stretch = r!(selector_children.get(1));
+    let label = r!(r!(children_query.get(*stretch)).first());
+    let mut text = r!(label_query.get_mut(*label));
+    if text.sections.first().is_some_and(|x| x.value == display) {
+        return;
+    }
+    text.sections = parse_rich(display);
+}
+
 fn quit_to_desktop(_: Trigger<Pointer<Click>>, mut app_exit: EventWriter<AppExit>) {
     if cfg!(not(feature = "web")) {
         app_exit.write(AppExit::Success);
@@ -1,4 +1,5 @@
 mod death;
+mod dialogue;
 mod gameover;
 mod main;
 mod pause;
@@ -46,6 +47,7 @@ pub enum Menu {
     Pause,
     GameOver,
     Death,
+    Dialogue,
 }
 
 impl Configure for Menu {
@@ -59,7 +61,13 @@ impl Configure for Menu {
                 Menu::ANY.on_disable(Pause::disable),
             ),
         );
-        app.add_plugins((main::plugin, pause::plugin, death::plugin, gameover::plugin));
+        app.add_plugins((
+            main::plugin,
+            pause::plugin,
+            death::plugin,
+            gameover::plugin,
+            dialogue::plugin,
+        ));
     }
 }
 
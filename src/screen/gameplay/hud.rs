@@ -0,0 +1,350 @@
+//! The heads-up display shown over the level during gameplay.
+
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::boss::ArenaLocked;
+use crate::game::boss::Boss;
+use crate::game::boss::BossDefeated;
+use crate::game::collectible::CollectibleAssets;
+use crate::game::collectible::CollectibleKind;
+use crate::game::collectible::CollectibleSystems;
+use crate::game::collectible::CollectibleTally;
+use crate::game::health::Difficulty;
+use crate::game::health::Health;
+use crate::game::health::Lives;
+use crate::game::player::Player;
+use crate::game::player_chain::CanShootChain;
+use crate::game::player_chain::ChainLength;
+use crate::game::player_chain::ShootingChain;
+use crate::game::run_stats::format_time;
+use crate::game::telemetry::LevelTime;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(
+        Hud,
+        HudLevelName,
+        HudTimer,
+        HudCollectibleCount,
+        HudGrapple,
+        HudGrappleFill,
+        HudLives,
+        HudBoss,
+        HudBossFill,
+    )>();
+
+    app.add_systems(StateFlush, Screen::Gameplay.on_enter(spawn_hud));
+    // Menus pause the game, so this covers every menu overlay too.
+    app.add_systems(StateFlush, Pause.on_edge(show_hud, hide_hud));
+    app.add_systems(
+        Update,
+        Screen::Gameplay
+            .on_update((
+                update_level_name,
+                update_timer,
+                update_collectible_counts.after(CollectibleSystems),
+                update_grapple,
+                update_lives,
+                update_boss_health,
+            ))
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Hud;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudLevelName;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudTimer;
+
+/// The collected and total counts for a kind of collectible.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudCollectibleCount(CollectibleKind);
+
+/// Shown while the player can shoot a chain.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudGrapple;
+
+/// Fills up as a shot chain reaches its target, and stays full while the grapple is ready.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudGrappleFill;
+
+/// Shown when the difficulty has lives.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudLives;
+
+/// Shown while fighting a boss.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudBoss;
+
+/// Shrinks as the boss loses health.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudBossFill;
+
+fn spawn_hud(
+    mut commands: Commands,
+    screen_root: Res<ScreenRoot>,
+    collectible_assets: Res<CollectibleAssets>,
+) {
+    let collectible_counts = CollectibleKind::ALL.map(|kind| {
+        (
+            Name::new(format!("{kind:?}Count")),
+            HudCollectibleCount(kind),
+            Node {
+                display: Display::None,
+                column_gap: Vw(0.8),
+                ..Node::ROW_MID
+            },
+            children![
+                (
+                    collectible_assets.icon(kind),
+                    Node {
+                        width: Vw(2.5),
+                        height: Vw(2.5),
+                        ..default()
+                    },
+                ),
+                widget::small_label(""),
+            ],
+        )
+    });
+
+    commands.entity(screen_root.ui).with_child((
+        Name::new("Hud"),
+        Hud,
+        Node {
+            justify_content: JustifyContent::SpaceBetween,
+            padding: UiRect::all(Vw(1.0)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("HudLeft"),
+                Node {
+                    row_gap: Vw(0.5),
+                    ..Node::COLUMN_LEFT
+                },
+                children![
+                    (HudLevelName, widget::small_label("")),
+                    (HudTimer, widget::small_label("")),
+                    (
+                        Name::new("CollectibleCounts"),
+                        Node {
+                            column_gap: Vw(2.0),
+                            ..Node::ROW_MID
+                        },
+                        Children::spawn(SpawnIter(collectible_counts.into_iter())),
+                    ),
+                ],
+            ),
+            (
+                Name::new("HudRight"),
+                Node {
+                    row_gap: Vw(0.5),
+                    ..Node::COLUMN_RIGHT
+                },
+                children![
+                    (
+                        HudLives,
+                        Node {
+                            display: Display::None,
+                            ..default()
+                        },
+                        widget::small_label(""),
+                    ),
+                    grapple_meter(),
+                ],
+            ),
+            boss_health_bar(),
+        ],
+    ));
+}
+
+fn grapple_meter() -> impl Bundle {
+    (
+        Name::new("GrappleMeter"),
+        HudGrapple,
+        Node {
+            display: Display::None,
+            row_gap: Vw(0.3),
+            ..Node::COLUMN_RIGHT
+        },
+        children![
+            widget::small_label("Grapple"),
+            (
+                Name::new("GrappleBar"),
+                Node {
+                    width: Vw(8.0),
+                    height: Vw(0.8),
+                    ..default()
+                },
+                ThemeColor::PrimaryDisabled.set::<BackgroundColor>(),
+                children![(
+                    Name::new("GrappleFill"),
+                    HudGrappleFill,
+                    Node::DEFAULT.full_size(),
+                    ThemeColor::Primary.set::<BackgroundColor>(),
+                )],
+            ),
+        ],
+    )
+}
+
+fn boss_health_bar() -> impl Bundle {
+    (
+        Name::new("BossHealthBar"),
+        HudBoss,
+        Node {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            bottom: Vw(2.0),
+            left: Percent(25.0),
+            width: Percent(50.0),
+            row_gap: Vw(0.3),
+            ..Node::COLUMN_MID
+        },
+        children![
+            widget::small_label("Boss"),
+            (
+                Name::new("BossHealth"),
+                Node {
+                    width: Percent(100.0),
+                    height: Vw(1.2),
+                    ..default()
+                },
+                ThemeColor::PrimaryDisabled.set::<BackgroundColor>(),
+                children![(
+                    Name::new("BossHealthFill"),
+                    HudBossFill,
+                    Node::DEFAULT.full_size(),
+                    ThemeColor::Primary.set::<BackgroundColor>(),
+                )],
+            ),
+        ],
+    )
+}
+
+fn show_hud(mut hud_query: Query<&mut Visibility, With<Hud>>) {
+    for mut visibility in &mut hud_query {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn hide_hud(mut hud_query: Query<&mut Visibility, With<Hud>>) {
+    for mut visibility in &mut hud_query {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Replaces the text of a label, if it has changed.
+fn set_label(text: &mut RichText, display: &str) {
+    if text.sections.first().is_some_and(|x| x.value == display) {
+        return;
+    }
+    text.sections = parse_rich(display);
+}
+
+fn set_shown(node: &mut Node, is_shown: bool) {
+    let display = if is_shown {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if node.display != display {
+        node.display = display;
+    }
+}
+
+fn update_level_name(
+    level_selection: Res<LevelSelection>,
+    mut label: Single<&mut RichText, With<HudLevelName>>,
+) {
+    let index = match &*level_selection {
+        LevelSelection::Indices(indices) => indices.level,
+        _ => 0,
+    };
+    set_label(&mut label, &format!("Level {}", index + 1));
+}
+
+fn update_timer(level_time: Res<LevelTime>, mut label: Single<&mut RichText, With<HudTimer>>) {
+    set_label(&mut label, &format_time(level_time.0));
+}
+
+fn update_collectible_counts(
+    tally: Single<&CollectibleTally>,
+    mut count_query: Query<(&HudCollectibleCount, &mut Node, &Children)>,
+    mut label_query: Query<&mut RichText>,
+) {
+    for (count, mut node, children) in &mut count_query {
+        let count = tally.get(count.0);
+        // Only show the kinds that the level has.
+        set_shown(&mut node, count.total > 0);
+
+        let mut label = c!(label_query.get_mut(*c!(children.get(1))));
+        set_label(&mut label, &format!("{}/{}", count.collected, count.total));
+    }
+}
+
+fn update_grapple(
+    player: Single<(Has<CanShootChain>, &Position), With<Player>>,
+    shooting_chain: Option<Single<(&ShootingChain, &ChainLength)>>,
+    mut grapple: Single<&mut Node, (With<HudGrapple>, Without<HudGrappleFill>)>,
+    mut fill: Single<&mut Node, (With<HudGrappleFill>, Without<HudGrapple>)>,
+) {
+    let (can_shoot, player_pos) = *player;
+    // Only show the grapple on levels where it's been unlocked.
+    set_shown(&mut grapple, can_shoot);
+
+    let ready = shooting_chain.map_or(1.0, |chain| {
+        let (shooting_chain, &length) = *chain;
+        shooting_chain.progress(length, player_pos.0)
+    });
+    fill.width = Percent(100.0 * ready);
+}
+
+fn update_lives(
+    difficulty: Res<Difficulty>,
+    lives: Res<Lives>,
+    player_health: Single<&Health, With<Player>>,
+    mut lives_label: Single<(&mut Node, &mut RichText), With<HudLives>>,
+) {
+    let (node, label) = &mut *lives_label;
+    set_shown(node, difficulty.has_lives());
+    set_label(
+        label,
+        &format!(
+            "Lives: {}  Health: {}/{}",
+            lives.0, player_health.current, player_health.max,
+        ),
+    );
+}
+
+fn update_boss_health(
+    boss_query: Query<&Health, (With<Boss>, Without<BossDefeated>)>,
+    arena_query: Query<(), With<ArenaLocked>>,
+    mut bar: Single<&mut Node, (With<HudBoss>, Without<HudBossFill>)>,
+    mut fill: Single<&mut Node, (With<HudBossFill>, Without<HudBoss>)>,
+) {
+    let health = boss_query.iter().next();
+    // Only show the bar once the arena has locked the player in.
+    set_shown(&mut bar, health.is_some() && !arena_query.is_empty());
+
+    if let Some(health) = health {
+        fill.width = Percent(100.0 * health.current as f32 / health.max.max(1) as f32);
+    }
+}
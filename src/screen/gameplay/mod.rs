@@ -1,9 +1,12 @@
+mod hud;
+
 use crate::menu::Menu;
 use crate::prelude::*;
 use crate::screen::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.configure::<GameplayAction>();
+    app.add_plugins(hud::plugin);
 
     app.add_state::<ShowPlayerDeathMenu>();
     app.add_state::<ShowGameOverMenu>();
@@ -3,6 +3,7 @@ pub mod gameplay;
 mod loading;
 mod title;
 
+use crate::core::camera::CAMERA_SCALE;
 use crate::core::camera::CameraRoot;
 use crate::core::window::WindowReady;
 use crate::menu::Menu;
@@ -74,9 +75,15 @@ impl Configure for Screen {
     }
 }
 
-fn reset_screen_camera(camera_root: Res<CameraRoot>, mut camera_query: Query<&mut Transform>) {
-    let mut transform = r!(camera_query.get_mut(camera_root.primary));
+fn reset_screen_camera(
+    camera_root: Res<CameraRoot>,
+    mut camera_query: Query<(&mut Transform, &mut Projection)>,
+) {
+    let (mut transform, mut projection) = r!(camera_query.get_mut(camera_root.primary));
     *transform = default();
+    if let Projection::Orthographic(projection) = &mut *projection {
+        projection.scale = CAMERA_SCALE;
+    }
 }
 
 /// The total time elapsed in the current screen.
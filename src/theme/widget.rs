@@ -97,6 +97,10 @@ pub fn label(text: impl AsRef<str>) -> impl Bundle {
     label_base(Vw(3.5), ThemeColor::BodyText, text)
 }
 
+pub fn small_label(text: impl AsRef<str>) -> impl Bundle {
+    label_base(Vw(2.5), ThemeColor::BodyText, text)
+}
+
 pub fn paragraph(text: &'static str) -> impl Bundle {
     (
         Name::new("Paragraph"),
@@ -8,6 +8,7 @@ pub mod late_commands;
 pub mod patch;
 pub mod previous;
 pub mod selection;
+pub mod trajectory;
 
 #[allow(unused_imports)]
 pub mod prelude {
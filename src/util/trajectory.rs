@@ -0,0 +1,122 @@
+//! Ballistic trajectories for bodies falling under constant gravity.
+
+use crate::prelude::*;
+
+/// The acceleration a body falls with, from the global [`Gravity`] and its [`GravityScale`].
+pub fn body_gravity(gravity: &Gravity, gravity_scale: Option<&GravityScale>) -> Vec2 {
+    gravity.0 * gravity_scale.map_or(1.0, |x| x.0)
+}
+
+/// Where a body launched from `start` will be after `time` seconds.
+pub fn position_at(start: Vec2, velocity: Vec2, gravity: Vec2, time: f32) -> Vec2 {
+    start + velocity * time + 0.5 * gravity * time * time
+}
+
+/// Positions along a trajectory every `interval` seconds, starting after the first interval.
+pub fn sample(
+    start: Vec2,
+    velocity: Vec2,
+    gravity: Vec2,
+    interval: f32,
+    count: usize,
+) -> impl Iterator<Item = Vec2> {
+    (1..=count).map(move |i| position_at(start, velocity, gravity, i as f32 * interval))
+}
+
+/// The launch velocity that reaches `target` after exactly `time` seconds.
+pub fn velocity_for_time(start: Vec2, target: Vec2, gravity: Vec2, time: f32) -> Vec2 {
+    (target - start - 0.5 * gravity * time * time) / time.max(f32::EPSILON)
+}
+
+/// The launch velocity with a fixed `speed` that reaches `target`, or `None` if it's out of range.
+///
+/// There are two arcs that reach any target in range, and the flatter one is picked unless
+/// `high_arc` is set.
+pub fn velocity_for_speed(
+    start: Vec2,
+    target: Vec2,
+    gravity: Vec2,
+    speed: f32,
+    high_arc: bool,
+) -> Option<Vec2> {
+    let (up, across, height, distance) = split_by_gravity(target - start, gravity);
+    let g = gravity.length();
+    if g < f32::EPSILON {
+        return Some((target - start).normalize_or_zero() * speed);
+    }
+
+    let speed2 = speed * speed;
+    let discriminant = speed2 * speed2 - g * (g * distance * distance + 2.0 * height * speed2);
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let root = if high_arc { root } else { -root };
+    let angle = (speed2 + root).atan2(g * distance);
+
+    Some((across * angle.cos() + up * angle.sin()) * speed)
+}
+
+/// The launch velocity with a fixed `speed` that travels furthest towards `target`.
+pub fn furthest_velocity(start: Vec2, target: Vec2, gravity: Vec2, speed: f32) -> Vec2 {
+    let (up, across, ..) = split_by_gravity(target - start, gravity);
+    (across + up).normalize() * speed
+}
+
+/// How long a body launched with `velocity` takes to cover the distance to `target` across gravity.
+pub fn time_to_reach(start: Vec2, target: Vec2, velocity: Vec2, gravity: Vec2) -> f32 {
+    let (_, across, _, distance) = split_by_gravity(target - start, gravity);
+    distance / velocity.dot(across).max(f32::EPSILON)
+}
+
+/// Splits an offset into its height against gravity and its distance across it,
+/// returning the up and across directions along with them.
+fn split_by_gravity(offset: Vec2, gravity: Vec2) -> (Vec2, Vec2, f32, f32) {
+    let up = (-gravity).try_normalize().unwrap_or(Vec2::Y);
+    let height = offset.dot(up);
+    let across = offset - up * height;
+    let distance = across.length();
+    let across = across.try_normalize().unwrap_or(up.perp());
+
+    (up, across, height, distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAVITY: Vec2 = Vec2::new(0.0, -500.0);
+
+    fn assert_hits(start: Vec2, target: Vec2, velocity: Vec2) {
+        let time = time_to_reach(start, target, velocity, GRAVITY);
+        let end = position_at(start, velocity, GRAVITY, time);
+        assert!(end.distance(target) < 0.1, "{end} should be {target}");
+    }
+
+    #[test]
+    fn velocity_for_speed_hits_target() {
+        let start = Vec2::new(10.0, 20.0);
+        for target in [vec2(200.0, 20.0), vec2(-150.0, 80.0), vec2(120.0, -60.0)] {
+            for high_arc in [false, true] {
+                let velocity = velocity_for_speed(start, target, GRAVITY, 400.0, high_arc).unwrap();
+                assert!((velocity.length() - 400.0).abs() < 0.01);
+                assert_hits(start, target, velocity);
+            }
+        }
+    }
+
+    #[test]
+    fn velocity_for_speed_out_of_range() {
+        assert_eq!(
+            velocity_for_speed(Vec2::ZERO, vec2(1000.0, 0.0), GRAVITY, 100.0, false),
+            None,
+        );
+    }
+
+    #[test]
+    fn velocity_for_time_hits_target() {
+        let (start, target) = (vec2(0.0, 0.0), vec2(300.0, 50.0));
+        let velocity = velocity_for_time(start, target, GRAVITY, 1.5);
+        assert!(position_at(start, velocity, GRAVITY, 1.5).distance(target) < 0.01);
+    }
+}